@@ -0,0 +1,233 @@
+//! Measures open and listing times of a synthetic encrypted image, with and
+//! without [`OpenOptions::preload_directories()`], and file read times of an
+//! unencrypted image opened from a slice and through a `Box<dyn Image>`.
+//!
+//! Run with `cargo bench -p orbis-pfs --bench open`.
+
+use aes::Aes128;
+use aes::cipher::KeyInit;
+use hmac::{Hmac, Mac};
+use orbis_pfs::OpenOptions;
+use orbis_pfs::directory::DirEntry;
+use orbis_pfs::fixture::ImageBuilder;
+use orbis_pfs::image::Image;
+use sha2::Sha256;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use xts_mode::{Xts128, get_tweak_default};
+
+const BS: usize = 0x10000;
+const SECTOR: usize = 0x1000;
+const INODE_SIZE: usize = 168;
+const DIRS: usize = 1000;
+const FILES_PER_DIR: usize = 30;
+const READ_FILES: usize = 64;
+const READ_FILE_SIZE: usize = 1 << 20;
+const RUNS: usize = 5;
+const EKPFS: [u8; 32] = [0x5a; 32];
+const KEY_SEED: [u8; 16] = [0xa5; 16];
+
+fn main() {
+    bench_preload();
+    bench_dispatch();
+}
+
+/// Compares opening, walking and looking up with and without preloading.
+fn bench_preload() {
+    let data = build_image();
+
+    println!(
+        "image: {} MiB, {DIRS} directories, {} files, block size {BS:#x}",
+        data.len() >> 20,
+        DIRS * FILES_PER_DIR,
+    );
+
+    for (name, preload) in [("default", false), ("preload_directories", true)] {
+        let mut options = OpenOptions::new();
+        options.preload_directories(preload);
+
+        let open = median(|| {
+            black_box(options.open_slice(&data, Some(&EKPFS)).unwrap());
+        });
+        let walk = median(|| {
+            let pfs = options.open_slice(&data, Some(&EKPFS)).unwrap();
+
+            for entry in pfs.walk() {
+                black_box(entry.unwrap());
+            }
+        });
+        let lookup = median(|| {
+            let pfs = options.open_slice(&data, Some(&EKPFS)).unwrap();
+
+            for d in 0..DIRS {
+                for f in 0..FILES_PER_DIR {
+                    let entry = pfs.lookup(&format!("d{d:04}/f{f:02}")).unwrap();
+                    assert!(matches!(entry, DirEntry::File(_)));
+                }
+            }
+        });
+
+        println!(
+            "{name:>20}: open {open:>10.2?}  open+walk {walk:>10.2?}  open+lookup all {lookup:>10.2?}"
+        );
+    }
+}
+
+/// Compares reading every file through the slice fast path of
+/// [`orbis_pfs::open_slice()`] and through dynamic dispatch on a
+/// `Box<dyn Image>`.
+fn bench_dispatch() {
+    let mut builder = ImageBuilder::new();
+
+    for i in 0..READ_FILES {
+        builder.file(
+            ImageBuilder::SUPER_ROOT,
+            format!("f{i:02}"),
+            vec![i as u8; READ_FILE_SIZE],
+        );
+    }
+
+    let data = builder.build();
+    let image: Box<dyn Image> = Box::new(SliceImage(&data));
+
+    println!(
+        "image: {} MiB, {READ_FILES} files, block size {:#x}",
+        data.len() >> 20,
+        ImageBuilder::BLOCK_SIZE,
+    );
+
+    let slice = orbis_pfs::open_slice(&data, None).unwrap();
+    let boxed = orbis_pfs::open_image(&*image).unwrap();
+
+    for (name, pfs) in [("open_slice", &slice), ("open_image(Box<dyn>)", &boxed)] {
+        let mut buf = vec![0u8; READ_FILE_SIZE];
+        let read = median(|| {
+            for (_, entry) in pfs.root().open().unwrap() {
+                let DirEntry::File(file) = entry else {
+                    unreachable!();
+                };
+
+                file.read_at(0, &mut buf).unwrap();
+                black_box(&buf);
+            }
+        });
+
+        println!("{name:>20}: read all {read:>10.2?}");
+    }
+}
+
+/// An in-memory image read through [`Image`], like any other backend.
+struct SliceImage<'a>(&'a [u8]);
+
+impl Image for SliceImage<'_> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+        let src = self.0.get(offset as usize..).unwrap_or_default();
+        let n = buf.len().min(src.len());
+
+        buf[..n].copy_from_slice(&src[..n]);
+        Ok(n)
+    }
+
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+}
+
+/// Returns the median duration of [`RUNS`] calls to `f`.
+fn median(mut f: impl FnMut()) -> Duration {
+    let mut times: Vec<_> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+
+    times.sort();
+    times[RUNS / 2]
+}
+
+/// Builds an encrypted image with a super-root holding [`DIRS`] directories
+/// of [`FILES_PER_DIR`] empty files each. Every directory takes one block.
+fn build_image() -> Vec<u8> {
+    let inode_count = 1 + DIRS + DIRS * FILES_PER_DIR;
+    let per_block = BS / INODE_SIZE;
+    let inode_blocks = inode_count.div_ceil(per_block);
+    let first_dir_block = 1 + inode_blocks;
+    let mut data = vec![0u8; (first_dir_block + 1 + DIRS) * BS];
+    let put = |data: &mut Vec<u8>, at: usize, v: &[u8]| data[at..at + v.len()].copy_from_slice(v);
+
+    // Header: version, format, encrypted mode, block size, inodes, super-root #0.
+    put(&mut data, 0x00, &1u64.to_le_bytes());
+    put(&mut data, 0x08, &20130315u64.to_le_bytes());
+    put(&mut data, 0x1c, &4u16.to_le_bytes());
+    put(&mut data, 0x20, &(BS as u32).to_le_bytes());
+    put(&mut data, 0x30, &(inode_count as u64).to_le_bytes());
+    put(&mut data, 0x40, &(inode_blocks as u64).to_le_bytes());
+    put(&mut data, 0x370, &KEY_SEED);
+
+    let inode_at = |ino: usize| (1 + ino / per_block) * BS + (ino % per_block) * INODE_SIZE;
+    let dirent =
+        |data: &mut Vec<u8>, block: usize, slot: usize, ino: usize, ty: u32, name: &str| {
+            let at = block * BS + slot * 24;
+
+            put(data, at, &(ino as u32).to_le_bytes());
+            put(data, at + 4, &ty.to_le_bytes());
+            put(data, at + 8, &(name.len() as u32).to_le_bytes());
+            put(data, at + 12, &24u32.to_le_bytes());
+            put(data, at + 16, name.as_bytes());
+        };
+
+    // Directory inodes (super-root included) each own one block.
+    for d in 0..=DIRS {
+        let at = inode_at(d);
+
+        put(&mut data, at, &0x41EDu16.to_le_bytes());
+        put(&mut data, at + 8, &(BS as u64).to_le_bytes());
+        put(&mut data, at + 0x60, &1u32.to_le_bytes());
+        put(
+            &mut data,
+            at + 100,
+            &((first_dir_block + d) as u32).to_le_bytes(),
+        );
+    }
+
+    for d in 0..DIRS {
+        dirent(&mut data, first_dir_block, d, 1 + d, 3, &format!("d{d:04}"));
+    }
+
+    for d in 0..DIRS {
+        for f in 0..FILES_PER_DIR {
+            let ino = 1 + DIRS + d * FILES_PER_DIR + f;
+
+            put(&mut data, inode_at(ino), &0x81A4u16.to_le_bytes());
+            dirent(
+                &mut data,
+                first_dir_block + 1 + d,
+                f,
+                ino,
+                2,
+                &format!("f{f:02}"),
+            );
+        }
+    }
+
+    encrypt(&mut data);
+    data
+}
+
+/// Encrypts every sector after the header block the way the console does.
+fn encrypt(data: &mut [u8]) {
+    let mut hmac = <Hmac<Sha256> as Mac>::new_from_slice(&EKPFS).unwrap();
+    hmac.update(&[0x01, 0x00, 0x00, 0x00]);
+    hmac.update(&KEY_SEED);
+
+    let secret = hmac.finalize().into_bytes();
+    let tweak_key = Aes128::new_from_slice(&secret[..16]).unwrap();
+    let data_key = Aes128::new_from_slice(&secret[16..]).unwrap();
+    let xts = Xts128::new(data_key, tweak_key);
+
+    for (i, sector) in data.chunks_exact_mut(SECTOR).enumerate().skip(BS / SECTOR) {
+        xts.encrypt_sector(sector, get_tweak_default(i as u128));
+    }
+}
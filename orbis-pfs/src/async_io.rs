@@ -0,0 +1,278 @@
+//! Asynchronous access to images and files for tokio-based applications.
+//!
+//! Enabled with the `async` feature. The PFS reader itself is synchronous;
+//! these adapters run its reads on tokio's blocking thread pool so they do
+//! not stall the async executor. [`BlockingImage`] adapts any [`Image`] to
+//! [`AsyncImage`], and [`AsyncFileReader`] streams a PFS file through
+//! [`tokio::io::AsyncRead`], e.g. into an HTTP response body.
+//!
+//! Reads are moved to another thread, so the images and files involved must
+//! be `'static`: an owned [`Image`] for [`BlockingImage`], and a PFS opened
+//! from one (e.g. with [`open_image()`](crate::open_image)) for files.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use orbis_pfs::directory::DirEntry;
+//! use orbis_pfs::image::FileImage;
+//! use tokio::io::AsyncReadExt;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let pfs = orbis_pfs::open_image(FileImage::open("image.pfs")?)?;
+//!
+//! if let DirEntry::File(file) = pfs.lookup("uroot/eboot.bin")? {
+//!     let mut reader = file.reader().into_async();
+//!     let mut contents = Vec::new();
+//!
+//!     reader.read_to_end(&mut contents).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::file::File;
+use crate::image::Image;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::task::JoinHandle;
+
+/// Largest read [`AsyncFileReader`] issues at once.
+const MAX_READ: usize = 1024 * 1024;
+
+/// The asynchronous counterpart of [`Image`].
+///
+/// Like [`Image`], reads are positional and take `&self`, so an image can be
+/// shared between tasks.
+pub trait AsyncImage: Send + Sync {
+    /// Reads bytes from the image at `offset` into `buf`.
+    ///
+    /// Returns the number of bytes read. A short read indicates the end of
+    /// the image was reached.
+    fn read_at(
+        &self,
+        offset: u64,
+        buf: &mut [u8],
+    ) -> impl Future<Output = io::Result<usize>> + Send;
+
+    /// Returns the total length of the image in bytes.
+    fn len(&self) -> u64;
+
+    /// Returns `true` if the image is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An [`AsyncImage`] that runs the reads of an [`Image`] on tokio's blocking
+/// thread pool.
+///
+/// Each read is performed into a temporary buffer on the pool and copied into
+/// the caller's buffer when it completes. Must be used from within a tokio
+/// runtime.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::async_io::{AsyncImage, BlockingImage};
+/// use orbis_pfs::image::Image;
+///
+/// struct Mem(Vec<u8>);
+///
+/// impl Image for Mem {
+///     fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+///         let data = self.0.get(offset as usize..).unwrap_or(&[]);
+///         let n = buf.len().min(data.len());
+///         buf[..n].copy_from_slice(&data[..n]);
+///         Ok(n)
+///     }
+///
+///     fn len(&self) -> u64 {
+///         self.0.len() as u64
+///     }
+/// }
+///
+/// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let image = BlockingImage::new(Mem(b"orbis".to_vec()));
+/// let mut buf = [0u8; 3];
+///
+/// let n = rt.block_on(image.read_at(2, &mut buf)).unwrap();
+///
+/// assert_eq!(&buf[..n], b"bis");
+/// ```
+pub struct BlockingImage<I: Image + 'static> {
+    inner: Arc<I>,
+}
+
+impl<I: Image + 'static> BlockingImage<I> {
+    /// Wraps `inner`.
+    pub fn new(inner: I) -> Self {
+        Self::from_arc(Arc::new(inner))
+    }
+
+    /// Wraps an image that is shared with other users.
+    pub fn from_arc(inner: Arc<I>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped image.
+    #[must_use]
+    pub fn inner(&self) -> &Arc<I> {
+        &self.inner
+    }
+}
+
+impl<I: Image + 'static> Clone for BlockingImage<I> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<I: Image + 'static> std::fmt::Debug for BlockingImage<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingImage")
+            .field("inner", &self.inner.describe())
+            .finish()
+    }
+}
+
+impl<I: Image + 'static> AsyncImage for BlockingImage<I> {
+    async fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let inner = self.inner.clone();
+        let data = spawn_read(buf.len(), move |tmp| inner.read_at(offset, tmp)).await?;
+
+        buf[..data.len()].copy_from_slice(&data);
+
+        Ok(data.len())
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+}
+
+/// A PFS [`File`] read through [`AsyncRead`].
+///
+/// Created via [`FileReader::into_async()`](crate::file::FileReader::into_async).
+/// Each read is performed on tokio's blocking thread pool, at most 1 MiB at a
+/// time. Must be used from within a tokio runtime.
+pub struct AsyncFileReader {
+    file: File<'static>,
+    pos: u64,
+    /// The read in flight, if any.
+    pending: Option<JoinHandle<io::Result<Vec<u8>>>>,
+    /// Data read but not yet returned, from `consumed` on.
+    buffered: Vec<u8>,
+    consumed: usize,
+}
+
+impl AsyncFileReader {
+    pub(crate) fn new(file: File<'static>, pos: u64) -> Self {
+        Self {
+            file,
+            pos,
+            pending: None,
+            buffered: Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    /// Returns the position of the next byte to be returned.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl std::fmt::Debug for AsyncFileReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncFileReader")
+            .field("file", &self.file)
+            .field("pos", &self.pos)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncRead for AsyncFileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        // Data left over from a read larger than the previous buffer is
+        // returned first; otherwise start a read or wait for the one in flight.
+        if this.consumed == this.buffered.len() {
+            let handle = this.pending.get_or_insert_with(|| {
+                let file = this.file.clone();
+                let pos = this.pos;
+                let len = buf.remaining().min(MAX_READ);
+
+                tokio::task::spawn_blocking(move || {
+                    let mut data = vec![0; len];
+                    let n = file.read_at(pos, &mut data)?;
+
+                    data.truncate(n);
+                    Ok(data)
+                })
+            });
+
+            let result = ready!(Pin::new(handle).poll(cx));
+
+            this.pending = None;
+            this.buffered = result.map_err(io::Error::other)??;
+            this.consumed = 0;
+        }
+
+        let rest = &this.buffered[this.consumed..];
+        let n = rest.len().min(buf.remaining());
+
+        buf.put_slice(&rest[..n]);
+        this.consumed += n;
+        this.pos += n as u64;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl File<'static> {
+    /// Reads file data at `offset` on tokio's blocking thread pool.
+    ///
+    /// This is [`read_at()`](Self::read_at) for async code. Must be called
+    /// from within a tokio runtime.
+    pub async fn read_at_async(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let file = self.clone();
+        let data = spawn_read(buf.len(), move |tmp| file.read_at(offset, tmp)).await?;
+
+        buf[..data.len()].copy_from_slice(&data);
+
+        Ok(data.len())
+    }
+}
+
+/// Runs `read` on the blocking thread pool with a buffer of `len` bytes and
+/// returns the bytes it read.
+async fn spawn_read(
+    len: usize,
+    read: impl FnOnce(&mut [u8]) -> io::Result<usize> + Send + 'static,
+) -> io::Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || {
+        let mut data = vec![0; len];
+        let n = read(&mut data)?;
+
+        data.truncate(n);
+        Ok(data)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
@@ -4,7 +4,9 @@ use self::dirent::Dirent;
 use crate::Pfs;
 use crate::file::File;
 use crate::inode::Inode;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 pub mod dirent;
@@ -16,6 +18,17 @@ pub enum OpenError {
     #[snafu(display("inode #{inode} is not valid"))]
     InvalidInode { inode: usize },
 
+    #[snafu(display("cannot load inode #{inode}"))]
+    LoadInode {
+        inode: usize,
+        source: crate::OpenError,
+    },
+
+    #[snafu(display("cannot load block map"))]
+    LoadBlockMap {
+        source: crate::inode::LoadBlocksError,
+    },
+
     #[snafu(display("cannot read block #{block}"))]
     ReadBlock { block: u32, source: std::io::Error },
 
@@ -27,6 +40,12 @@ pub enum OpenError {
 
     #[snafu(display("dirent #{dirent} in block #{block} has unknown type"))]
     DirentUnknownType { block: u32, dirent: usize },
+
+    #[snafu(display("directory inode #{inode} is linked more than once"))]
+    DirectoryCycle { inode: usize },
+
+    #[snafu(display("inode #{inode} is not a directory"))]
+    NotDirectory { inode: usize },
 }
 
 /// Represents a directory in the PFS.
@@ -58,6 +77,8 @@ pub enum OpenError {
 pub struct Directory<'a> {
     pfs: Arc<Pfs<'a>>,
     inode: usize,
+    /// Parent inode, if known from the directory walk.
+    parent: Option<usize>,
 }
 
 impl<'a> std::fmt::Debug for Directory<'a> {
@@ -70,8 +91,12 @@ impl<'a> std::fmt::Debug for Directory<'a> {
 }
 
 impl<'a> Directory<'a> {
-    pub(super) fn new(pfs: Arc<Pfs<'a>>, inode: usize) -> Self {
-        Self { pfs, inode }
+    pub(super) fn new(pfs: Arc<Pfs<'a>>, inode: usize, parent: Option<usize>) -> Self {
+        Self { pfs, inode, parent }
+    }
+
+    pub(crate) fn inode_index(&self) -> usize {
+        self.inode
     }
 
     #[must_use]
@@ -144,21 +169,223 @@ impl<'a> Directory<'a> {
 
     /// Opens this directory and reads its entries.
     ///
-    /// Returns a collection of directory entries (files and subdirectories).
+    /// Returns a collection of directory entries (files, subdirectories and
+    /// symbolic links).
+    ///
+    /// Directories too large for the 12 direct block pointers continue through
+    /// the inode's indirect blocks, and every entry is still returned:
+    ///
+    /// ```
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // 3000 dirents of 24 bytes take 18 blocks, past the 12 direct pointers.
+    /// let mut builder = ImageBuilder::new();
+    /// let empty = builder.file(ImageBuilder::SUPER_ROOT, "f0000", Vec::new());
+    ///
+    /// for n in 1..3000 {
+    ///     builder.link(ImageBuilder::SUPER_ROOT, format!("f{n:04}"), empty);
+    /// }
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let entries = pfs.root().open().unwrap();
+    ///
+    /// assert_eq!(entries.len(), 3000);
+    /// assert!(entries.get(b"f0000").is_some());
+    /// assert!(entries.get(b"f2999").is_some());
+    /// ```
     pub fn open(&self) -> Result<DirEntries<'a>, OpenError> {
-        let blocks = self.pfs.block_map(self.inode);
-        let block_size = self.pfs.block_size;
-        let img = self.pfs.image();
-
-        // Read all dirents.
         let mut items: BTreeMap<Vec<u8>, DirEntry<'a>> = BTreeMap::new();
-        let mut block_data = vec![0; block_size as usize];
 
-        for &block_num in blocks {
-            // Read block data via positional read.
-            let offset = (block_num as u64) * (block_size as u64);
+        self.read_dirents(|block, num, dirent| {
+            if let Some(entry) = self.make_entry(block, num, &dirent)? {
+                items.insert(dirent.name().to_vec(), entry);
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })?;
+
+        Ok(DirEntries { items })
+    }
+
+    /// Opens this directory and reads its entries in on-disk order.
+    ///
+    /// Unlike [`open()`](Self::open), entries are returned in the order their
+    /// dirents appear in the directory blocks, which is what repacking and
+    /// verification tools need to reproduce the exact layout.
+    ///
+    /// ```
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// let mut builder = ImageBuilder::new();
+    ///
+    /// for name in ["b", "c", "a"] {
+    ///     builder.file(ImageBuilder::SUPER_ROOT, name, Vec::new());
+    /// }
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let root = pfs.root();
+    ///
+    /// let ordered: Vec<_> = root
+    ///     .open_ordered()
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|(name, _)| name)
+    ///     .collect();
+    /// assert_eq!(ordered, [b"b", b"c", b"a"]);
+    ///
+    /// // open() sorts the same entries by name.
+    /// let sorted: Vec<_> = root.open().unwrap().into_iter().map(|(name, _)| name).collect();
+    /// assert_eq!(sorted, [b"a", b"b", b"c"]);
+    /// ```
+    pub fn open_ordered(&self) -> Result<Vec<(Vec<u8>, DirEntry<'a>)>, OpenError> {
+        let mut items = Vec::new();
+
+        self.read_dirents(|block, num, dirent| {
+            if let Some(entry) = self.make_entry(block, num, &dirent)? {
+                items.push((dirent.name().to_vec(), entry));
+            }
+
+            Ok(ControlFlow::Continue(()))
+        })?;
+
+        Ok(items)
+    }
+
+    /// Lists this directory with the type and size of each entry.
+    ///
+    /// Entries are returned in on-disk order, like
+    /// [`open_ordered()`](Self::open_ordered). Sizes come from the child
+    /// inodes, which are already loaded, so no [`File`] or [`Directory`] is
+    /// constructed for them. This is what a FUSE `readdir` needs.
+    pub fn list_stat(&self) -> Result<Vec<DirStatEntry>, OpenError> {
+        let mut items = Vec::new();
+
+        self.read_dirents(|block, num, dirent| {
+            let Some(file_type) = self.entry_type(block, num, &dirent)? else {
+                return Ok(ControlFlow::Continue(()));
+            };
+            let inode = dirent.inode();
+
+            items.push(DirStatEntry {
+                name: dirent.name().to_vec(),
+                inode,
+                file_type,
+                size: self.pfs.inode(inode).size(),
+            });
+
+            Ok(ControlFlow::Continue(()))
+        })?;
+
+        Ok(items)
+    }
+
+    /// Finds a single entry by name.
+    ///
+    /// Unlike [`open()`](Self::open), this stops at the first match and does
+    /// not collect the other entries. Returns `None` if there is no such entry.
+    pub fn find(&self, name: &[u8]) -> Result<Option<DirEntry<'a>>, OpenError> {
+        let mut found = None;
+
+        self.read_dirents(|block, num, dirent| {
+            if dirent.name() != name {
+                return Ok(ControlFlow::Continue(()));
+            }
+
+            found = self.make_entry(block, num, &dirent)?;
+
+            Ok(ControlFlow::Break(()))
+        })?;
+
+        Ok(found)
+    }
+
+    /// Returns the parent of this directory.
+    ///
+    /// The parent is known without I/O for directories obtained from
+    /// [`open()`](Self::open). Otherwise it is resolved through the `..` entry,
+    /// which requires reading the directory. Returns `None` for the super-root,
+    /// or [`OpenError::NotDirectory`] if the `..` entry refers to an inode that
+    /// is not a directory.
+    ///
+    /// ```
+    /// use orbis_pfs::directory::{DirEntry, OpenError};
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// let mut builder = ImageBuilder::new();
+    /// let dir = builder.dir(ImageBuilder::SUPER_ROOT, "dir");
+    /// let file = builder.file(dir, "f", b"data".as_slice());
+    /// let mut data = builder.build();
+    ///
+    /// // Add a `..` entry to `dir`, in block 3 after the entry for `f`, that
+    /// // refers to the file.
+    /// let at = 3 * ImageBuilder::BLOCK_SIZE + 24;
+    /// data[at..at + 4].copy_from_slice(&(file as u32).to_le_bytes());
+    /// data[at + 4..at + 8].copy_from_slice(&5u32.to_le_bytes());
+    /// data[at + 8..at + 12].copy_from_slice(&2u32.to_le_bytes());
+    /// data[at + 12..at + 16].copy_from_slice(&24u32.to_le_bytes());
+    /// data[at + 16..at + 18].copy_from_slice(b"..");
+    ///
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let Ok(DirEntry::File(f)) = pfs.lookup("dir/f") else {
+    ///     panic!("no file");
+    /// };
+    ///
+    /// // The directory of a file does not know its own parent.
+    /// assert!(matches!(
+    ///     f.parent().parent(),
+    ///     Err(OpenError::NotDirectory { .. })
+    /// ));
+    /// ```
+    pub fn parent(&self) -> Result<Option<Directory<'a>>, OpenError> {
+        let parent = match self.parent {
+            Some(v) => Some(v),
+            None => {
+                let mut parent = None;
+
+                self.read_dirents(|_, _, dirent| {
+                    if dirent.ty() == Dirent::PARENT {
+                        parent = Some(dirent.inode());
+                        return Ok(ControlFlow::Break(()));
+                    }
+
+                    Ok(ControlFlow::Continue(()))
+                })?;
+
+                // The entry comes from the image, so a corrupt one may refer to a file.
+                if let Some(inode) = parent {
+                    ensure!(
+                        self.pfs.inode(inode).is_directory(),
+                        NotDirectorySnafu { inode }
+                    );
+                }
+
+                parent
+            }
+        };
+
+        // The super-root is its own parent.
+        Ok(parent
+            .filter(|&p| p != self.inode)
+            .map(|p| Directory::new(self.pfs.clone(), p, None)))
+    }
+
+    /// Reads the dirents of this directory in on-disk order, passing each one
+    /// along with its block number and index within the block to `f`.
+    ///
+    /// Inode numbers are validated before `f` is called.
+    fn read_dirents(
+        &self,
+        mut f: impl FnMut(u32, usize, Dirent) -> Result<ControlFlow<()>, OpenError>,
+    ) -> Result<(), OpenError> {
+        let blocks = self.pfs.block_map(self.inode).context(LoadBlockMapSnafu)?;
+        let mut block_data = vec![0; self.pfs.block_size as usize];
 
-            img.read_exact_at(offset, &mut block_data)
+        for &block_num in blocks {
+            // Read block data via positional read (or the preloaded copy).
+            self.pfs
+                .read_dir_block(block_num, &mut block_data)
                 .context(ReadBlockSnafu { block: block_num })?;
 
             // Read dirents in the block.
@@ -185,28 +412,63 @@ impl<'a> Directory<'a> {
                 // Check if inode valid.
                 let inode = dirent.inode();
                 ensure!(inode < self.pfs.inode_count(), InvalidInodeSnafu { inode });
+                self.pfs
+                    .load_inode(inode)
+                    .context(LoadInodeSnafu { inode })?;
 
-                // Construct object.
-                let entry = match dirent.ty() {
-                    Dirent::FILE => DirEntry::File(File::new(self.pfs.clone(), inode)),
-                    Dirent::DIRECTORY => {
-                        DirEntry::Directory(Directory::new(self.pfs.clone(), inode))
-                    }
-                    Dirent::SELF | Dirent::PARENT => continue,
-                    _ => {
-                        return Err(DirentUnknownTypeSnafu {
-                            block: block_num,
-                            dirent: num,
-                        }
-                        .build());
-                    }
-                };
-
-                items.insert(dirent.name().to_vec(), entry);
+                if f(block_num, num, dirent)?.is_break() {
+                    return Ok(());
+                }
             }
         }
 
-        Ok(DirEntries { items })
+        Ok(())
+    }
+
+    /// Constructs the entry for `dirent`, or `None` for `.` and `..`.
+    fn make_entry(
+        &self,
+        block: u32,
+        num: usize,
+        dirent: &Dirent,
+    ) -> Result<Option<DirEntry<'a>>, OpenError> {
+        let inode = dirent.inode();
+        let Some(ty) = self.entry_type(block, num, dirent)? else {
+            return Ok(None);
+        };
+
+        let entry = match ty {
+            DirEntryType::Directory => {
+                DirEntry::Directory(Directory::new(self.pfs.clone(), inode, Some(self.inode)))
+            }
+            DirEntryType::File => DirEntry::File(File::new(self.pfs.clone(), inode, self.inode)),
+            DirEntryType::Symlink => {
+                DirEntry::Symlink(File::new(self.pfs.clone(), inode, self.inode))
+            }
+        };
+
+        Ok(Some(entry))
+    }
+
+    /// Returns the type of the entry for `dirent`, or `None` for `.` and `..`.
+    fn entry_type(
+        &self,
+        block: u32,
+        num: usize,
+        dirent: &Dirent,
+    ) -> Result<Option<DirEntryType>, OpenError> {
+        // Links are recognized by their inode mode, whatever the dirent type says.
+        let is_symlink = self.pfs.inode(dirent.inode()).is_symlink();
+
+        let ty = match dirent.ty() {
+            Dirent::SELF | Dirent::PARENT => return Ok(None),
+            Dirent::DIRECTORY => DirEntryType::Directory,
+            _ if is_symlink => DirEntryType::Symlink,
+            Dirent::FILE => DirEntryType::File,
+            _ => return DirentUnknownTypeSnafu { block, dirent: num }.fail(),
+        };
+
+        Ok(Some(ty))
     }
 
     fn inode_ref(&self) -> &Inode {
@@ -259,6 +521,15 @@ impl<'a> DirEntries<'a> {
     pub fn names(&self) -> impl Iterator<Item = &[u8]> {
         self.items.keys().map(|k| k.as_slice())
     }
+
+    /// Consumes the entries and returns them as a vector.
+    ///
+    /// Entries are ordered by their raw name bytes, not by any locale or
+    /// Unicode collation, so the order is deterministic across platforms.
+    #[must_use]
+    pub fn into_sorted_vec(self) -> Vec<(Vec<u8>, DirEntry<'a>)> {
+        self.items.into_iter().collect()
+    }
 }
 
 impl<'a> IntoIterator for DirEntries<'a> {
@@ -323,7 +594,90 @@ impl<'a> Iterator for DirEntriesOwnedIter<'a> {
 
 impl ExactSizeIterator for DirEntriesOwnedIter<'_> {}
 
-/// Represents an entry in a directory (either a file or subdirectory).
+/// A lazy depth-first iterator over a directory tree.
+///
+/// Created by [`Pfs::walk()`](crate::Pfs::walk). Each directory is read only
+/// when the walk reaches it, and its entries are yielded in name order before
+/// moving on to its siblings.
+///
+/// A directory whose inode was already entered is not entered again; the walk
+/// yields [`OpenError::DirectoryCycle`] for it and carries on, so a corrupt
+/// image with a dirent pointing back at an ancestor still ends:
+///
+/// ```
+/// use orbis_pfs::directory::OpenError;
+/// use orbis_pfs::fixture::ImageBuilder;
+///
+/// let mut builder = ImageBuilder::new();
+/// let sub = builder.dir(ImageBuilder::SUPER_ROOT, "sub");
+/// builder.link(sub, "loop", ImageBuilder::SUPER_ROOT);
+///
+/// let data = builder.build();
+/// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+/// let results: Vec<_> = pfs.walk().collect();
+///
+/// assert_eq!(results.len(), 3);
+/// assert!(matches!(results[2], Err(OpenError::DirectoryCycle { inode: 0 })));
+/// assert!(pfs.manifest().is_err());
+/// ```
+#[derive(Debug)]
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct Walk<'a> {
+    /// Directories being iterated, innermost last.
+    stack: Vec<(PathBuf, DirEntriesOwnedIter<'a>)>,
+    /// A directory that was just yielded and is entered on the next call.
+    pending: Option<(PathBuf, Directory<'a>)>,
+    /// Inodes of the directories entered so far.
+    visited: HashSet<usize>,
+}
+
+impl<'a> Walk<'a> {
+    pub(crate) fn new(root: Directory<'a>) -> Self {
+        Self {
+            stack: Vec::new(),
+            pending: Some((PathBuf::new(), root)),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<(PathBuf, DirEntry<'a>), OpenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((path, dir)) = self.pending.take() {
+            let inode = dir.inode_index();
+
+            if !self.visited.insert(inode) {
+                return Some(DirectoryCycleSnafu { inode }.fail());
+            }
+
+            match dir.open() {
+                Ok(entries) => self.stack.push((path, entries.into_iter())),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        loop {
+            let (parent, entries) = self.stack.last_mut()?;
+
+            let Some((name, entry)) = entries.next() else {
+                self.stack.pop();
+                continue;
+            };
+
+            let path = parent.join(String::from_utf8_lossy(&name).as_ref());
+
+            if let DirEntry::Directory(dir) = &entry {
+                self.pending = Some((path.clone(), dir.clone()));
+            }
+
+            return Some(Ok((path, entry)));
+        }
+    }
+}
+
+/// Represents an entry in a directory (a file, subdirectory or symbolic link).
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum DirEntry<'a> {
@@ -331,4 +685,31 @@ pub enum DirEntry<'a> {
     Directory(Directory<'a>),
     /// A file.
     File(File<'a>),
+    /// A symbolic link. Use [`File::read_link()`] to get its target.
+    Symlink(File<'a>),
+}
+
+/// Type of an entry in a [`DirStatEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DirEntryType {
+    /// A subdirectory.
+    Directory,
+    /// A file.
+    File,
+    /// A symbolic link.
+    Symlink,
+}
+
+/// An entry returned by [`Directory::list_stat()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirStatEntry {
+    /// Raw name bytes, not guaranteed to be valid UTF-8.
+    pub name: Vec<u8>,
+    /// Index of the inode.
+    pub inode: usize,
+    /// Type of the entry.
+    pub file_type: DirEntryType,
+    /// Logical size in bytes, from the inode.
+    pub size: u64,
 }
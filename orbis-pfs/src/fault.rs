@@ -0,0 +1,168 @@
+//! Fault injection for testing error paths.
+//!
+//! Enabled with the `test-util` feature.
+
+use crate::image::Image;
+use std::io;
+
+/// A single fault applied by [`FaultInjectImage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// XORs the byte at `offset` with `mask` whenever it is read.
+    Flip { offset: u64, mask: u8 },
+    /// Ends any read that reaches `offset` early, as if the image ended there.
+    ShortRead { offset: u64 },
+    /// Fails any read that covers `offset` with an error of `kind`.
+    Error { offset: u64, kind: io::ErrorKind },
+}
+
+/// An [`Image`] adapter that corrupts reads of an inner image at configured offsets.
+///
+/// Faults are checked on every [`read_at()`](Image::read_at) call, so results
+/// are deterministic regardless of how reads are split.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::fault::FaultInjectImage;
+/// use orbis_pfs::image::Image;
+/// use std::io;
+/// #
+/// # struct Mem(Vec<u8>);
+/// #
+/// # impl Image for Mem {
+/// #     fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+/// #         let data = self.0.get(offset as usize..).unwrap_or_default();
+/// #         let n = buf.len().min(data.len());
+/// #         buf[..n].copy_from_slice(&data[..n]);
+/// #         Ok(n)
+/// #     }
+/// #
+/// #     fn len(&self) -> u64 {
+/// #         self.0.len() as u64
+/// #     }
+/// # }
+///
+/// let image = FaultInjectImage::new(Mem(vec![0u8; 0x30]))
+///     .flip(0x08, 0xff)
+///     .short_read(0x18)
+///     .error(0x20, io::ErrorKind::InvalidData);
+///
+/// // Reads before any fault pass through unchanged.
+/// let mut buf = [0u8; 8];
+/// assert_eq!(image.read_at(0, &mut buf).unwrap(), 8);
+/// assert_eq!(buf, [0; 8]);
+///
+/// // The flipped byte is corrupted on every read that covers it.
+/// assert_eq!(image.read_at(8, &mut buf).unwrap(), 8);
+/// assert_eq!(buf[0], 0xff);
+///
+/// // A read reaching the short-read offset stops just before it.
+/// assert_eq!(image.read_at(0x10, &mut buf[..8]).unwrap(), 8);
+/// assert_eq!(image.read_at(0x14, &mut buf).unwrap(), 4);
+///
+/// // A read covering the error offset fails with the configured kind.
+/// let err = image.read_at(0x1c, &mut buf).unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+///
+/// // Reads past the faulted range succeed again.
+/// assert_eq!(image.read_at(0x28, &mut buf).unwrap(), 8);
+/// ```
+#[derive(Debug)]
+pub struct FaultInjectImage<I: Image> {
+    inner: I,
+    faults: Vec<Fault>,
+}
+
+impl<I: Image> FaultInjectImage<I> {
+    /// Wraps `inner` without any faults.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            faults: Vec::new(),
+        }
+    }
+
+    /// Adds a [`Fault::Flip`].
+    #[must_use]
+    pub fn flip(self, offset: u64, mask: u8) -> Self {
+        self.fault(Fault::Flip { offset, mask })
+    }
+
+    /// Adds a [`Fault::ShortRead`].
+    #[must_use]
+    pub fn short_read(self, offset: u64) -> Self {
+        self.fault(Fault::ShortRead { offset })
+    }
+
+    /// Adds a [`Fault::Error`].
+    #[must_use]
+    pub fn error(self, offset: u64, kind: io::ErrorKind) -> Self {
+        self.fault(Fault::Error { offset, kind })
+    }
+
+    /// Adds an arbitrary fault.
+    #[must_use]
+    pub fn fault(mut self, fault: Fault) -> Self {
+        self.faults.push(fault);
+        self
+    }
+
+    /// Returns the configured faults.
+    #[must_use]
+    pub fn faults(&self) -> &[Fault] {
+        &self.faults
+    }
+
+    /// Consumes the adapter and returns the inner image.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: Image> Image for FaultInjectImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        let end = offset + output_buf.len() as u64;
+        let mut len = output_buf.len();
+
+        for fault in &self.faults {
+            match *fault {
+                Fault::Error { offset: at, kind } if (offset..end).contains(&at) => {
+                    return Err(io::Error::new(
+                        kind,
+                        format!("injected fault at offset {at:#x}"),
+                    ));
+                }
+                Fault::ShortRead { offset: at } if (offset..end).contains(&at) => {
+                    len = len.min((at - offset) as usize);
+                }
+                _ => {}
+            }
+        }
+
+        let n = self.inner.read_at(offset, &mut output_buf[..len])?;
+
+        for fault in &self.faults {
+            if let Fault::Flip { offset: at, mask } = *fault
+                && (offset..offset + n as u64).contains(&at)
+            {
+                output_buf[(at - offset) as usize] ^= mask;
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "FaultInjectImage(faults={}) over {}",
+            self.faults.len(),
+            self.inner.describe()
+        )
+    }
+}
@@ -1,10 +1,16 @@
 use crate::Pfs;
+use crate::directory::Directory;
 use crate::image::Image;
 use crate::inode::Inode;
 use std::cmp::min;
-use std::io::{self, Error, Read, Seek, SeekFrom};
+use std::io::{self, Error, IoSliceMut, Read, Seek, SeekFrom};
+use std::ops::Range;
 use std::sync::Arc;
 
+/// Longest symbolic link target [`File::read_link()`] accepts, which is
+/// `PATH_MAX` on the console.
+pub const SYMLINK_MAX: u64 = 1024;
+
 /// Represents a file in the PFS.
 ///
 /// Use [`read_at()`](Self::read_at) for positional reads (thread-safe, `&self`),
@@ -33,6 +39,8 @@ use std::sync::Arc;
 pub struct File<'a> {
     pfs: Arc<Pfs<'a>>,
     inode: usize,
+    /// Inode of the directory this file was found in.
+    parent: usize,
 }
 
 impl<'a> std::fmt::Debug for File<'a> {
@@ -46,8 +54,17 @@ impl<'a> std::fmt::Debug for File<'a> {
 }
 
 impl<'a> File<'a> {
-    pub(crate) fn new(pfs: Arc<Pfs<'a>>, inode: usize) -> Self {
-        Self { pfs, inode }
+    pub(crate) fn new(pfs: Arc<Pfs<'a>>, inode: usize, parent: usize) -> Self {
+        Self { pfs, inode, parent }
+    }
+
+    pub(crate) fn inode_index(&self) -> usize {
+        self.inode
+    }
+
+    /// Returns the directory this file was found in.
+    pub fn parent(&self) -> Directory<'a> {
+        Directory::new(self.pfs.clone(), self.parent, None)
     }
 
     #[must_use]
@@ -138,6 +155,42 @@ impl<'a> File<'a> {
         self.len() == 0
     }
 
+    /// Returns `true` if the inode mode marks this entry as a symbolic link.
+    #[must_use]
+    pub fn is_symlink(&self) -> bool {
+        self.inode_ref().is_symlink()
+    }
+
+    /// Reads the target of a symbolic link.
+    ///
+    /// The target path is stored as the inode's data and returned as raw bytes.
+    /// Returns [`io::ErrorKind::InvalidInput`] if this file is not a symbolic
+    /// link, and [`io::ErrorKind::InvalidData`] if the target is longer than
+    /// [`SYMLINK_MAX`] bytes.
+    pub fn read_link(&self) -> io::Result<Vec<u8>> {
+        if !self.is_symlink() {
+            return Err(Error::new(
+                io::ErrorKind::InvalidInput,
+                "not a symbolic link",
+            ));
+        }
+
+        // The size comes from the image and is untrusted.
+        let len = self.len();
+
+        if len > SYMLINK_MAX {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("symbolic link target of {len} bytes is too long"),
+            ));
+        }
+
+        let mut target = vec![0; len as usize];
+        self.reader().read_exact(&mut target)?;
+
+        Ok(target)
+    }
+
     /// Returns the file contents as a borrowed slice without a copy.
     ///
     /// This returns `Some` only when **all** of the following are true:
@@ -167,7 +220,7 @@ impl<'a> File<'a> {
         let start = (start_block as u64) * block_size;
         let end = start + inode.size();
 
-        data.get(start as usize..end as usize)
+        data.get(usize::try_from(start).ok()?..usize::try_from(end).ok()?)
     }
 
     /// Reads file data at the given offset without modifying any cursor.
@@ -179,6 +232,178 @@ impl<'a> File<'a> {
         pfs_read_at(&self.pfs, self.inode, offset, buf)
     }
 
+    /// Returns the byte ranges of the image that hold this file's data.
+    ///
+    /// Ranges are in file order, with physically adjacent blocks merged, and
+    /// the last range ends with the file rather than its final block. This
+    /// lets callers relate a file to offset-based metadata about the image,
+    /// such as PlayGo chunk ranges. For a compressed file the ranges hold the
+    /// PFSC stream, so they add up to [`compressed_len()`](Self::compressed_len).
+    /// An empty file has no ranges.
+    ///
+    /// Blocks past the single indirect block are found through the double
+    /// (and deeper) indirect trees:
+    ///
+    /// ```
+    /// use orbis_pfs::directory::DirEntry;
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // 12 direct blocks, a full single indirect block and 2 double indirect ones.
+    /// let blocks = 12 + 0x1000 / 4 + 2;
+    /// let mut contents = vec![0u8; blocks * 0x1000];
+    /// contents[(blocks - 1) * 0x1000..][..4].copy_from_slice(b"end!");
+    ///
+    /// let mut builder = ImageBuilder::new();
+    /// builder.file(ImageBuilder::SUPER_ROOT, "big", contents);
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let Some(DirEntry::File(file)) = pfs.root().open().unwrap().remove(b"big") else {
+    ///     panic!("no file");
+    /// };
+    ///
+    /// // The builder stores the blocks in one run, so they merge into one range.
+    /// let extents = file.extents().unwrap();
+    /// assert_eq!(extents.len(), 1);
+    /// assert_eq!(extents[0].end - extents[0].start, file.len());
+    ///
+    /// // The last block is the second entry of the double indirect tree.
+    /// let mut buf = [0u8; 4];
+    /// file.read_at(file.len() - 0x1000, &mut buf).unwrap();
+    /// assert_eq!(&buf, b"end!");
+    /// ```
+    ///
+    /// A compressed file covers only the blocks of its PFSC stream:
+    ///
+    /// ```
+    /// use orbis_pfs::directory::DirEntry;
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // A compressed file of three blocks, stored in blocks 3 and 4.
+    /// let mut builder = ImageBuilder::new();
+    /// let packed = builder.file(ImageBuilder::SUPER_ROOT, "packed", vec![0u8; 0x1100]);
+    /// builder.compressed(packed, 3 * 0x1000);
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let Some(DirEntry::File(file)) = pfs.root().open().unwrap().remove(b"packed") else {
+    ///     panic!("no file");
+    /// };
+    ///
+    /// assert_eq!(file.extents().unwrap(), [3 * 0x1000..3 * 0x1000 + 0x1100]);
+    /// ```
+    pub fn extents(&self) -> io::Result<Vec<Range<u64>>> {
+        let inode = self.inode_ref();
+        let size = if inode.flags().is_compressed() {
+            inode.compressed_len()
+        } else {
+            inode.size()
+        };
+
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let block_map = self.pfs.block_map(self.inode).map_err(Error::other)?;
+        let block_size = self.pfs.block_size as u64;
+        let count = size.div_ceil(block_size) as usize;
+        let blocks = block_map.get(..count).ok_or_else(|| {
+            Error::other(format!(
+                "inode #{} has {} of {} blocks",
+                self.inode,
+                block_map.len(),
+                count
+            ))
+        })?;
+
+        let mut extents: Vec<Range<u64>> = Vec::new();
+
+        for (i, &block) in blocks.iter().enumerate() {
+            let start = (block as u64) * block_size;
+            let len = min(block_size, size - (i as u64) * block_size);
+
+            match extents.last_mut() {
+                Some(last) if last.end == start => last.end += len,
+                _ => extents.push(start..start + len),
+            }
+        }
+
+        Ok(extents)
+    }
+
+    /// Returns `true` if this file's data is one run of adjacent blocks.
+    ///
+    /// See [`block_range()`](Self::block_range).
+    #[must_use]
+    pub fn is_contiguous(&self) -> bool {
+        self.block_range().is_some()
+    }
+
+    /// Returns the byte offset and length of this file's data in the image,
+    /// if its blocks are adjacent.
+    ///
+    /// Unlike [`as_slice()`](Self::as_slice), this also works for compressed
+    /// files and encrypted images. A copy loop can then read the whole file
+    /// with one large `read_at` on the image the PFS was opened from, instead
+    /// of going block by block. For an encrypted image the offset is in the
+    /// decrypted view. For a compressed file the range holds the PFSC stream,
+    /// so its length is [`compressed_len()`](Self::compressed_len). Returns
+    /// `None` for an empty file, for fragmented blocks, or if the block map
+    /// cannot be loaded.
+    ///
+    /// ```
+    /// use orbis_pfs::directory::DirEntry;
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // A compressed file of three blocks, stored in blocks 3 and 4.
+    /// let mut builder = ImageBuilder::new();
+    /// let packed = builder.file(ImageBuilder::SUPER_ROOT, "packed", vec![0u8; 0x1100]);
+    /// builder.compressed(packed, 3 * 0x1000);
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let Some(DirEntry::File(file)) = pfs.root().open().unwrap().remove(b"packed") else {
+    ///     panic!("no file");
+    /// };
+    ///
+    /// assert!(file.is_compressed());
+    /// assert_eq!(file.block_range(), Some((3 * 0x1000, 0x1100)));
+    /// ```
+    #[must_use]
+    pub fn block_range(&self) -> Option<(u64, u64)> {
+        let inode = self.inode_ref();
+        let block_size = self.pfs.block_size as u64;
+        let len = if inode.flags().is_compressed() {
+            inode.compressed_len()
+        } else {
+            inode.size()
+        };
+
+        if len == 0 {
+            return None;
+        }
+
+        // Blocks marked contiguous need no block map.
+        if let Some((start, count)) = inode.contiguous_blocks()
+            && len <= count as u64 * block_size
+        {
+            return Some((start as u64 * block_size, len));
+        }
+
+        let block_map = self.pfs.block_map(self.inode).ok()?;
+        let blocks = block_map.get(..len.div_ceil(block_size) as usize)?;
+        let adjacent = blocks.windows(2).all(|w| w[0].checked_add(1) == Some(w[1]));
+
+        adjacent.then(|| (blocks[0] as u64 * block_size, len))
+    }
+
+    /// Checks the signatures of this file's blocks.
+    ///
+    /// See [`Pfs::verify_file_integrity()`] for details.
+    pub fn verify_integrity(&self) -> Result<(), crate::IntegrityError> {
+        self.pfs.verify_file_integrity(self.inode)
+    }
+
     /// Creates a [`FileReader`] that implements [`Read`] and [`Seek`].
     ///
     /// This is useful when you need to pass a PFS file to APIs that expect
@@ -233,6 +458,7 @@ impl<'a> Clone for File<'a> {
         Self {
             pfs: self.pfs.clone(),
             inode: self.inode,
+            parent: self.parent,
         }
     }
 }
@@ -260,6 +486,61 @@ impl Read for FileReader<'_> {
         self.pos += n as u64;
         Ok(n)
     }
+
+    /// Fills `bufs` in order from the current position.
+    ///
+    /// When the file is directly addressable (see [`File::as_slice()`]) the
+    /// buffers are copied from the mapped data without going through
+    /// [`File::read_at()`]. Otherwise each non-empty buffer is filled with a
+    /// single positional read, stopping early at the end of the file or at
+    /// an error after some data was read.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut total = 0;
+
+        if let Some(data) = self.file.as_slice() {
+            let mut rest = usize::try_from(self.pos)
+                .ok()
+                .and_then(|pos| data.get(pos..))
+                .unwrap_or(&[]);
+
+            for buf in bufs {
+                let n = min(buf.len(), rest.len());
+
+                buf[..n].copy_from_slice(&rest[..n]);
+                rest = &rest[n..];
+                total += n;
+            }
+        } else {
+            for buf in bufs.iter_mut().filter(|b| !b.is_empty()) {
+                // Report what was read so far; the error recurs on the next read.
+                let n = match self.file.read_at(self.pos + total as u64, buf) {
+                    Ok(n) => n,
+                    Err(e) if total == 0 => return Err(e),
+                    Err(_) => break,
+                };
+
+                total += n;
+
+                if n < buf.len() {
+                    break;
+                }
+            }
+        }
+
+        self.pos += total as u64;
+        Ok(total)
+    }
+}
+
+#[cfg(feature = "async")]
+impl FileReader<'static> {
+    /// Converts this reader into a [`tokio::io::AsyncRead`] starting at the
+    /// current position.
+    ///
+    /// See [`AsyncFileReader`](crate::async_io::AsyncFileReader).
+    pub fn into_async(self) -> crate::async_io::AsyncFileReader {
+        crate::async_io::AsyncFileReader::new(self.file, self.pos)
+    }
 }
 
 impl Seek for FileReader<'_> {
@@ -286,10 +567,15 @@ impl Seek for FileReader<'_> {
 
 /// A file within a PFS, exposed as an [`Image`] for chaining.
 ///
-/// This adapter maps logical file offsets through the inode's precomputed block
-/// map to physical offsets in the underlying PFS image. It is used to open
-/// nested PFS images (e.g. `pfs_image.dat` inside an outer PFS), optionally
-/// wrapped in [`PfscImage`][crate::pfsc::PfscImage] for decompression.
+/// This adapter maps logical file offsets through the inode's block map to
+/// physical offsets in the underlying PFS image. It is used to open nested PFS
+/// images (e.g. `pfs_image.dat` inside an outer PFS), optionally wrapped in
+/// [`PfscImage`][crate::pfsc::PfscImage] for decompression.
+///
+/// Its length is the inode size, clamped to the bytes the block map covers.
+/// An inode that declares more data than it has blocks for is exposed as
+/// shorter rather than failing reads near the end with "block not available".
+/// [`File::read_at()`] keeps reporting those reads as errors.
 ///
 /// Created via [`File::into_image()`].
 #[derive(Clone)]
@@ -309,22 +595,39 @@ impl<'a> std::fmt::Debug for PfsFileImage<'a> {
 
 impl Image for PfsFileImage<'_> {
     fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> std::io::Result<usize> {
-        pfs_read_at(&self.pfs, self.inode, offset, output_buf)
+        let available = self.len().saturating_sub(offset);
+        let len = min(output_buf.len() as u64, available) as usize;
+
+        pfs_read_at(&self.pfs, self.inode, offset, &mut output_buf[..len])
     }
 
     fn len(&self) -> u64 {
-        self.pfs.inode(self.inode).size()
+        let size = self.pfs.inode(self.inode).size();
+
+        // A block map that cannot be loaded is reported by the first read.
+        match self.pfs.block_map(self.inode) {
+            Ok(map) => min(size, map.len() as u64 * self.pfs.block_size as u64),
+            Err(_) => size,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "PfsFileImage(inode={}) over {}",
+            self.inode,
+            self.pfs.image().describe()
+        )
     }
 }
 
-fn pfs_read_at(pfs: &Pfs<'_>, inode: usize, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+fn pfs_read_at(pfs: &Arc<Pfs<'_>>, inode: usize, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
     let file_size = pfs.inode(inode).size();
 
     if buf.is_empty() || offset >= file_size {
         return Ok(0);
     }
 
-    let block_map = pfs.block_map(inode);
+    let block_map = pfs.block_map(inode).map_err(Error::other)?;
     let block_size = pfs.block_size as u64;
     let image = pfs.image();
     let mut copied = 0usize;
@@ -338,8 +641,8 @@ fn pfs_read_at(pfs: &Pfs<'_>, inode: usize, offset: u64, buf: &mut [u8]) -> io::
             Some(&v) => v,
             None => {
                 return Err(Error::other(format!(
-                    "block #{} is not available",
-                    block_index
+                    "block #{} of inode #{} is not available",
+                    block_index, inode
                 )));
             }
         };
@@ -350,7 +653,25 @@ fn pfs_read_at(pfs: &Pfs<'_>, inode: usize, offset: u64, buf: &mut [u8]) -> io::
 
         let phys_offset = (block_num as u64) * block_size + offset_in_block;
 
-        image.read_exact_at(phys_offset, &mut buf[copied..copied + to_read])?;
+        let dst = &mut buf[copied..copied + to_read];
+
+        // Slice-backed unencrypted images are indexed directly, skipping the
+        // dynamic dispatch through `Image` on every block.
+        match pfs
+            .data
+            .and_then(|d| d.get(usize::try_from(phys_offset).ok()?..)?.get(..to_read))
+        {
+            Some(src) => dst.copy_from_slice(src),
+            None => image.read_exact_at(phys_offset, dst).map_err(|e| {
+                Error::new(
+                    e.kind(),
+                    format!(
+                        "cannot read block #{} of inode #{} (physical block #{} at offset {:#x}): {}",
+                        block_index, inode, block_num, phys_offset, e
+                    ),
+                )
+            })?,
+        }
 
         copied += to_read;
         pos += to_read as u64;
@@ -0,0 +1,295 @@
+//! Builder for small unencrypted PFS images used in tests.
+//!
+//! Enabled with the `test-util` feature.
+
+/// Size of an unsigned inode on disk.
+const INODE_SIZE: usize = 168;
+
+/// Number of inodes in each block of the inode table. Inodes never straddle a
+/// block.
+const INODES_PER_BLOCK: usize = ImageBuilder::BLOCK_SIZE / INODE_SIZE;
+
+/// Number of direct block pointers in an inode.
+const DIRECT: usize = 12;
+
+const S_IFDIR: u16 = 0x41ed;
+const S_IFREG: u16 = 0x81a4;
+
+const DIRENT_FILE: u32 = 2;
+const DIRENT_DIRECTORY: u32 = 3;
+
+/// An inode added to an [`ImageBuilder`].
+#[derive(Debug, Clone)]
+struct Node {
+    mode: u16,
+    flags: u32,
+    size: Option<u64>,
+    /// File contents, as stored in the image.
+    data: Vec<u8>,
+    /// Directory entries as `(inode, type, name)`.
+    dirents: Vec<(usize, u32, Vec<u8>)>,
+}
+
+impl Node {
+    fn new(mode: u16) -> Self {
+        Self {
+            mode,
+            flags: 0,
+            size: None,
+            data: Vec::new(),
+            dirents: Vec::new(),
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.mode == S_IFDIR
+    }
+}
+
+/// Builds an unencrypted, uncompressed PFS image with a 4 KiB block size.
+///
+/// Inode #0 is the super-root. Every inode gets its blocks in one run after
+/// the inode table, followed by the indirect blocks it needs, so files and
+/// directories of any size up to the double indirect limit can be built.
+/// Dirents are written in the order they are added.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::directory::DirEntry;
+/// use orbis_pfs::fixture::ImageBuilder;
+///
+/// let mut builder = ImageBuilder::new();
+/// let uroot = builder.dir(ImageBuilder::SUPER_ROOT, "uroot");
+/// builder.file(uroot, "eboot.bin", b"ELF".as_slice());
+///
+/// let data = builder.build();
+/// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+///
+/// let Ok(DirEntry::File(file)) = pfs.lookup("uroot/eboot.bin") else {
+///     panic!("no file");
+/// };
+/// assert_eq!(file.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImageBuilder {
+    nodes: Vec<Node>,
+}
+
+impl ImageBuilder {
+    /// Block size of the built image.
+    pub const BLOCK_SIZE: usize = 0x1000;
+
+    /// Inode index of the super-root.
+    pub const SUPER_ROOT: usize = 0;
+
+    /// Creates a builder holding only an empty super-root.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(S_IFDIR)],
+        }
+    }
+
+    /// Adds a directory named `name` to `parent` and returns its inode.
+    pub fn dir(&mut self, parent: usize, name: impl AsRef<[u8]>) -> usize {
+        self.nodes.push(Node::new(S_IFDIR));
+
+        let inode = self.nodes.len() - 1;
+        self.link(parent, name, inode);
+        inode
+    }
+
+    /// Adds a file named `name` with the contents `data` to `parent` and
+    /// returns its inode.
+    pub fn file(
+        &mut self,
+        parent: usize,
+        name: impl AsRef<[u8]>,
+        data: impl Into<Vec<u8>>,
+    ) -> usize {
+        let mut node = Node::new(S_IFREG);
+        node.data = data.into();
+        self.nodes.push(node);
+
+        let inode = self.nodes.len() - 1;
+        self.link(parent, name, inode);
+        inode
+    }
+
+    /// Adds an entry named `name` to `parent` that refers to the existing
+    /// `inode`.
+    ///
+    /// Linking a directory into one of its own descendants builds a cycle.
+    pub fn link(&mut self, parent: usize, name: impl AsRef<[u8]>, inode: usize) {
+        let ty = if self.nodes[inode].is_dir() {
+            DIRENT_DIRECTORY
+        } else {
+            DIRENT_FILE
+        };
+
+        assert!(
+            self.nodes[parent].is_dir(),
+            "inode #{parent} is not a directory"
+        );
+
+        self.nodes[parent]
+            .dirents
+            .push((inode, ty, name.as_ref().to_vec()));
+    }
+
+    /// Marks file `inode` as compressed with a logical size of `size`.
+    ///
+    /// The data the file was added with becomes its stored PFSC stream.
+    pub fn compressed(&mut self, inode: usize, size: u64) -> &mut Self {
+        let node = &mut self.nodes[inode];
+
+        node.flags |= 0x1;
+        node.size = Some(size);
+        self
+    }
+
+    /// Overrides the size recorded in `inode` without changing its blocks.
+    pub fn size(&mut self, inode: usize, size: u64) -> &mut Self {
+        self.nodes[inode].size = Some(size);
+        self
+    }
+
+    /// Lays out the image and returns its bytes.
+    #[must_use]
+    pub fn build(&self) -> Vec<u8> {
+        const BS: usize = ImageBuilder::BLOCK_SIZE;
+
+        let contents: Vec<Vec<u8>> = self.nodes.iter().map(Self::contents).collect();
+        let inode_blocks = self.nodes.len().div_ceil(INODES_PER_BLOCK);
+        let mut data = vec![0u8; (1 + inode_blocks) * BS];
+
+        // Header: version, format, block size, inode count, inode blocks and
+        // super-root #0.
+        put(&mut data, 0x00, &1u64.to_le_bytes());
+        put(&mut data, 0x08, &20130315u64.to_le_bytes());
+        put(&mut data, 0x20, &(BS as u32).to_le_bytes());
+        put(&mut data, 0x30, &(self.nodes.len() as u64).to_le_bytes());
+        put(&mut data, 0x40, &(inode_blocks as u64).to_le_bytes());
+
+        for (index, (node, contents)) in self.nodes.iter().zip(&contents).enumerate() {
+            let at = BS + index / INODES_PER_BLOCK * BS + index % INODES_PER_BLOCK * INODE_SIZE;
+            let first = data.len() / BS;
+            let count = contents.len().div_ceil(BS);
+
+            data.extend_from_slice(contents);
+            data.resize((first + count) * BS, 0);
+
+            let blocks: Vec<u32> = (first..first + count).map(|b| b as u32).collect();
+            let indirect = write_indirect(&mut data, &blocks);
+            let size = node.size.unwrap_or(if node.is_dir() {
+                (count * BS) as u64
+            } else {
+                node.data.len() as u64
+            });
+            let stored = if node.flags & 0x1 != 0 {
+                contents.len() as u64
+            } else {
+                size
+            };
+
+            put(&mut data, at, &node.mode.to_le_bytes());
+            put(&mut data, at + 0x04, &node.flags.to_le_bytes());
+            put(&mut data, at + 0x08, &size.to_le_bytes());
+            put(&mut data, at + 0x10, &stored.to_le_bytes());
+            put(&mut data, at + 0x60, &(count as u32).to_le_bytes());
+
+            for (i, block) in blocks.iter().take(DIRECT).enumerate() {
+                put(&mut data, at + 100 + i * 4, &block.to_le_bytes());
+            }
+
+            for (i, block) in indirect.iter().enumerate() {
+                put(&mut data, at + 148 + i * 4, &block.to_le_bytes());
+            }
+        }
+
+        data
+    }
+
+    /// Returns the bytes stored in the blocks of `node`.
+    fn contents(node: &Node) -> Vec<u8> {
+        if !node.is_dir() {
+            return node.data.clone();
+        }
+
+        // Dirents are padded to 8 bytes and never cross a block.
+        let mut out = Vec::new();
+
+        for (inode, ty, name) in &node.dirents {
+            let entsize = (16 + name.len()).next_multiple_of(8);
+
+            if out.len() % Self::BLOCK_SIZE + entsize > Self::BLOCK_SIZE {
+                out.resize(out.len().next_multiple_of(Self::BLOCK_SIZE), 0);
+            }
+
+            let start = out.len();
+
+            out.extend_from_slice(&(*inode as u32).to_le_bytes());
+            out.extend_from_slice(&ty.to_le_bytes());
+            out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(entsize as u32).to_le_bytes());
+            out.extend_from_slice(name);
+            out.resize(start + entsize, 0);
+        }
+
+        out
+    }
+}
+
+impl Default for ImageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Appends the indirect blocks mapping `blocks` past the direct pointers and
+/// returns the single and double indirect block numbers to store in the inode.
+fn write_indirect(data: &mut Vec<u8>, blocks: &[u32]) -> Vec<u32> {
+    const PTRS: usize = ImageBuilder::BLOCK_SIZE / 4;
+
+    let rest = blocks.get(DIRECT..).unwrap_or_default();
+
+    assert!(
+        rest.len() <= PTRS + PTRS * PTRS,
+        "files beyond the double indirect limit are not supported"
+    );
+
+    if rest.is_empty() {
+        return Vec::new();
+    }
+
+    let (single, double) = rest.split_at(rest.len().min(PTRS));
+    let mut roots = vec![append_pointers(data, single)];
+
+    if !double.is_empty() {
+        let children: Vec<u32> = double
+            .chunks(PTRS)
+            .map(|chunk| append_pointers(data, chunk))
+            .collect();
+
+        roots.push(append_pointers(data, &children));
+    }
+
+    roots
+}
+
+/// Appends a block holding `pointers` and returns its number.
+fn append_pointers(data: &mut Vec<u8>, pointers: &[u32]) -> u32 {
+    let block = data.len() / ImageBuilder::BLOCK_SIZE;
+
+    for pointer in pointers {
+        data.extend_from_slice(&pointer.to_le_bytes());
+    }
+
+    data.resize((block + 1) * ImageBuilder::BLOCK_SIZE, 0);
+    block as u32
+}
+
+fn put(data: &mut [u8], at: usize, v: &[u8]) {
+    data[at..at + v.len()].copy_from_slice(v);
+}
@@ -22,6 +22,9 @@ pub enum ReadError {
 
     #[snafu(display("source buffer is too short to read the key seed"))]
     ReadKeySeedFailed,
+
+    #[snafu(display("no backup superblock is available"))]
+    NoBackup,
 }
 
 use zerocopy::byteorder::little_endian::{U16, U32, U64};
@@ -30,7 +33,7 @@ const VERSION: u64 = 1;
 const FORMAT: u64 = 20130315;
 
 /// The size of the full PFS header on disk (includes key seed area).
-pub(crate) const HEADER_SIZE: usize = 0x380;
+pub const HEADER_SIZE: usize = 0x380;
 
 /// Contains PFS header.
 ///
@@ -101,6 +104,27 @@ impl PfsHeader {
         })
     }
 
+    /// Parses the backup superblock referenced by the primary header at the start of `data`.
+    ///
+    /// The primary header is not validated; only its `nbackup` and block size
+    /// fields are used to locate the backup, which is then parsed with
+    /// [`Self::from_bytes()`].
+    pub(super) fn from_backup(data: &[u8]) -> Result<Self, ReadError> {
+        let (raw_header, _) =
+            PfsHeaderRaw::read_from_prefix(data).map_err(|_| ReadHeaderFailedSnafu.build())?;
+
+        let offset = u64::from(raw_header.nbackup.get())
+            .checked_mul(raw_header.block_size.get().into())
+            .filter(|&v| v != 0)
+            .context(NoBackupSnafu)?;
+        let backup = usize::try_from(offset)
+            .ok()
+            .and_then(|v| data.get(v..))
+            .context(NoBackupSnafu)?;
+
+        Self::from_bytes(backup)
+    }
+
     pub fn mode(&self) -> Mode {
         self.raw_header.mode
     }
@@ -119,6 +143,16 @@ impl PfsHeader {
         self.raw_header.ndinodeblock.get() as u32
     }
 
+    /// Returns `true` if the image declares itself read-only.
+    pub fn is_readonly(&self) -> bool {
+        self.raw_header.flags.ronly != 0
+    }
+
+    /// Gets the block holding the backup superblock, or 0 if there is none.
+    pub fn backup_block(&self) -> u32 {
+        self.raw_header.nbackup.get()
+    }
+
     pub fn super_root_inode(&self) -> usize {
         self.raw_header.superroot_ino.get() as usize
     }
@@ -1,8 +1,14 @@
+use crate::inode::{FromRawError, Inode};
+use crate::{DecryptError, decrypt_error};
 use aes::Aes128;
 use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
+use snafu::OptionExt;
 use std::cmp::min;
-use std::io;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
 use xts_mode::{Xts128, get_tweak_default};
 
 pub(crate) const XTS_BLOCK_SIZE: usize = 0x1000;
@@ -12,6 +18,22 @@ pub(crate) const XTS_BLOCK_SIZE: usize = 0x1000;
 /// This trait provides thread-safe, stateless access to PFS image data.
 /// Unlike `Read + Seek`, each call specifies its own offset, enabling
 /// concurrent reads from multiple threads without synchronization.
+///
+/// The trait is object safe, so any implementation can be erased to
+/// `dyn Image`. Shared references also implement it, which lets generic code
+/// accept both borrowed and type-erased images:
+///
+/// ```
+/// use orbis_pfs::image::Image;
+///
+/// fn total_len<I: Image>(images: &[I]) -> u64 {
+///     images.iter().map(|i| i.len()).sum()
+/// }
+///
+/// fn erased(images: &[&dyn Image]) -> u64 {
+///     total_len(images)
+/// }
+/// ```
 pub trait Image: Send + Sync {
     /// Reads bytes from the image at the given offset into `buf`.
     ///
@@ -49,6 +71,941 @@ pub trait Image: Send + Sync {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Streams the whole image into `w`, reading `buf_size` bytes at a time.
+    ///
+    /// Returns the number of bytes written. Takes `&mut dyn Write` so the trait
+    /// stays usable as `dyn Image`.
+    fn dump_to(&self, w: &mut dyn Write, buf_size: usize) -> io::Result<u64> {
+        let mut buf = vec![0u8; buf_size.max(1)];
+        let mut offset = 0u64;
+
+        loop {
+            let n = match self.read_at(offset, &mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            w.write_all(&buf[..n])?;
+            offset += n as u64;
+        }
+
+        Ok(offset)
+    }
+
+    /// Describes the layers this image is built from, outermost first.
+    ///
+    /// Adapters override this to name themselves and append the description
+    /// of the image they wrap, producing something like
+    /// `PfscImage(block_size=65536) over EncryptedImage(start=16) over UnencryptedSlice(len=8589934592)`.
+    /// This is meant for logs and diagnostics; the format is not stable.
+    ///
+    /// The default returns the type name of the implementation.
+    ///
+    /// ```
+    /// use orbis_pfs::image::{ClampedImage, Image};
+    /// # use std::io;
+    /// #
+    /// # struct Mem;
+    /// #
+    /// # impl Image for Mem {
+    /// #     fn read_at(&self, _: u64, _: &mut [u8]) -> io::Result<usize> {
+    /// #         Ok(0)
+    /// #     }
+    /// #
+    /// #     fn len(&self) -> u64 {
+    /// #         0x2000
+    /// #     }
+    /// # }
+    ///
+    /// let image = ClampedImage::new(Mem, 0x1000);
+    ///
+    /// assert!(image.describe().starts_with("ClampedImage(len=4096) over "));
+    /// assert!(image.describe().ends_with("Mem"));
+    /// ```
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_owned()
+    }
+}
+
+/// An [`Image`] whose backing storage can be modified in place.
+///
+/// Writes go straight to the backing storage, so they are durable once the
+/// storage is flushed. Like reads, writes are positional and take `&self`.
+pub trait WritableImage: Image {
+    /// Writes all of `data` at `offset`.
+    ///
+    /// Writes are in-place updates: returns [`io::ErrorKind::InvalidInput`] if
+    /// the range extends past [`Image::len()`].
+    fn write_at(&self, offset: u64, data: &[u8]) -> io::Result<()>;
+}
+
+impl<T: Image + ?Sized> Image for &T {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read_at(offset, output_buf)
+    }
+
+    fn read_exact_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_exact_at(offset, output_buf)
+    }
+
+    fn len(&self) -> u64 {
+        (**self).len()
+    }
+
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+
+    fn dump_to(&self, w: &mut dyn Write, buf_size: usize) -> io::Result<u64> {
+        (**self).dump_to(w, buf_size)
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+/// An [`Image`] adapter that exposes only the first `len` bytes of another image.
+///
+/// Reads past the boundary return fewer bytes (or none) instead of reaching the
+/// inner image, so a tool can inspect the start of a huge or truncated image and
+/// treat the rest as unavailable. [`Image::read_exact_at()`] past the boundary
+/// still fails with [`io::ErrorKind::UnexpectedEof`].
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use orbis_pfs::image::{ClampedImage, FileImage};
+///
+/// // Only look at the first 64 MiB.
+/// let image = ClampedImage::new(FileImage::open("image.pfs")?, 64 << 20);
+/// let pfs = orbis_pfs::open_image(image)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ClampedImage<I: Image> {
+    inner: I,
+    len: u64,
+}
+
+impl<I: Image> ClampedImage<I> {
+    /// Wraps `inner`, hiding everything from offset `len` onward.
+    pub fn new(inner: I, len: u64) -> Self {
+        Self { inner, len }
+    }
+
+    /// Returns the wrapped image.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: Image> Image for ClampedImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        if offset >= self.len {
+            return Ok(0);
+        }
+
+        let n = min(output_buf.len() as u64, self.len - offset) as usize;
+
+        self.inner.read_at(offset, &mut output_buf[..n])
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "ClampedImage(len={}) over {}",
+            self.len,
+            self.inner.describe()
+        )
+    }
+}
+
+/// Wraps `image` in a [`CheckedImage`] that asserts the [`Image`] contract.
+///
+/// A development aid for writing a new [`Image`] backend: run the PFS reader
+/// or your own tests over the wrapped image and any contract violation panics
+/// at the offending read, instead of surfacing later as corrupt data or a
+/// reader that never terminates.
+///
+/// # Example
+///
+/// ```should_panic
+/// use orbis_pfs::image::{self, Image};
+/// use std::io;
+///
+/// // Ignores EOF and always claims a full read.
+/// struct Broken;
+///
+/// impl Image for Broken {
+///     fn read_at(&self, _: u64, buf: &mut [u8]) -> io::Result<usize> {
+///         buf.fill(0);
+///         Ok(buf.len())
+///     }
+///
+///     fn len(&self) -> u64 {
+///         16
+///     }
+/// }
+///
+/// let image = image::checked(Broken);
+/// let mut buf = [0u8; 32];
+///
+/// image.read_at(0, &mut buf).unwrap(); // panics: returned 32 bytes at 0 of 16
+/// ```
+pub fn checked<I: Image>(image: I) -> CheckedImage<I> {
+    CheckedImage::new(image)
+}
+
+/// An [`Image`] adapter that checks every read of another image against the
+/// [`Image`] contract. Created by [`checked()`].
+///
+/// Each [`read_at()`](Image::read_at) of the inner image must:
+///
+/// - return no more than the buffer length;
+/// - not return bytes past [`len()`](Image::len), and return 0 at or past it;
+/// - make progress, i.e. return at least one byte when the buffer is not
+///   empty and the offset is before the end.
+///
+/// The length must also stay the same for the lifetime of the image.
+///
+/// The checks are debug assertions: a violation panics in debug builds with
+/// a message naming the image, and release builds pass reads through
+/// unchanged.
+#[derive(Debug)]
+pub struct CheckedImage<I: Image> {
+    inner: I,
+    len: u64,
+}
+
+impl<I: Image> CheckedImage<I> {
+    /// Wraps `inner`, recording its current length.
+    pub fn new(inner: I) -> Self {
+        let len = inner.len();
+
+        Self { inner, len }
+    }
+
+    /// Returns the wrapped image.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: Image> Image for CheckedImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read_at(offset, output_buf)?;
+        let len = self.inner.len();
+
+        debug_assert_eq!(
+            len,
+            self.len,
+            "{}: len() changed from {} to {len}",
+            self.inner.describe(),
+            self.len
+        );
+        debug_assert!(
+            n <= output_buf.len(),
+            "{}: read_at({offset}) returned {n} bytes into a {}-byte buffer",
+            self.inner.describe(),
+            output_buf.len()
+        );
+        debug_assert!(
+            n as u64 <= len.saturating_sub(offset),
+            "{}: read_at({offset}) returned {n} bytes at {offset} of {len}",
+            self.inner.describe()
+        );
+        debug_assert!(
+            n > 0 || output_buf.is_empty() || offset >= len,
+            "{}: read_at({offset}) returned 0 bytes before the end ({len})",
+            self.inner.describe()
+        );
+
+        Ok(n)
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!("CheckedImage over {}", self.inner.describe())
+    }
+}
+
+/// An [`Image`] that presents several images back to back as one.
+///
+/// This is the building block for split PKGs and multi-part dumps: offsets
+/// are routed to the part that contains them, and a read that straddles a
+/// boundary continues into the next part. [`Image::len()`] is the sum of the
+/// part lengths.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::image::{ConcatImage, Image};
+/// # use std::io;
+/// #
+/// # struct Mem(Vec<u8>);
+/// #
+/// # impl Image for Mem {
+/// #     fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+/// #         let data = self.0.get(offset as usize..).unwrap_or_default();
+/// #         let n = buf.len().min(data.len());
+/// #         buf[..n].copy_from_slice(&data[..n]);
+/// #         Ok(n)
+/// #     }
+/// #
+/// #     fn len(&self) -> u64 {
+/// #         self.0.len() as u64
+/// #     }
+/// # }
+///
+/// let image = ConcatImage::new(vec![
+///     Box::new(Mem(b"abc".to_vec())),
+///     Box::new(Mem(Vec::new())),
+///     Box::new(Mem(b"de".to_vec())),
+///     Box::new(Mem(b"fgh".to_vec())),
+/// ]);
+/// assert_eq!(image.len(), 8);
+///
+/// // A read straddling every boundary, including the empty part.
+/// let mut buf = [0u8; 6];
+/// assert_eq!(image.read_at(1, &mut buf).unwrap(), 6);
+/// assert_eq!(&buf, b"bcdefg");
+///
+/// // A read starting exactly on a boundary.
+/// let mut buf = [0u8; 2];
+/// image.read_exact_at(5, &mut buf).unwrap();
+/// assert_eq!(&buf, b"fg");
+///
+/// // Reads are cut short at the end of the last part.
+/// let mut buf = [0u8; 4];
+/// assert_eq!(image.read_at(6, &mut buf).unwrap(), 2);
+/// assert_eq!(&buf[..2], b"gh");
+/// assert_eq!(image.read_at(8, &mut buf).unwrap(), 0);
+/// ```
+pub struct ConcatImage {
+    parts: Vec<Box<dyn Image>>,
+    /// Offset of each part within the concatenation.
+    starts: Vec<u64>,
+    len: u64,
+}
+
+impl ConcatImage {
+    /// Concatenates `parts` in order.
+    pub fn new(parts: Vec<Box<dyn Image>>) -> Self {
+        let mut starts = Vec::with_capacity(parts.len());
+        let mut len = 0u64;
+
+        for part in &parts {
+            starts.push(len);
+            len += part.len();
+        }
+
+        Self { parts, starts, len }
+    }
+
+    /// Returns the parts.
+    pub fn into_parts(self) -> Vec<Box<dyn Image>> {
+        self.parts
+    }
+}
+
+impl std::fmt::Debug for ConcatImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcatImage")
+            .field("starts", &self.starts)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Image for ConcatImage {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        if offset >= self.len {
+            return Ok(0);
+        }
+
+        // Last part starting at or before the offset, which skips empty parts.
+        let mut index = self.starts.partition_point(|&s| s <= offset) - 1;
+        let mut total = 0;
+
+        while total < output_buf.len() && index < self.parts.len() {
+            let part = &self.parts[index];
+            let part_offset = offset + total as u64 - self.starts[index];
+
+            if part_offset >= part.len() {
+                index += 1;
+                continue;
+            }
+
+            let n = min((output_buf.len() - total) as u64, part.len() - part_offset) as usize;
+            let n = part.read_at(part_offset, &mut output_buf[total..(total + n)])?;
+
+            if n == 0 {
+                // The part is shorter than it claims; report what we have.
+                break;
+            }
+
+            total += n;
+        }
+
+        Ok(total)
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn describe(&self) -> String {
+        let parts: Vec<String> = self.parts.iter().map(|p| p.describe()).collect();
+
+        format!("ConcatImage[{}]", parts.join(", "))
+    }
+}
+
+/// An [`Image`] adapter that keeps the most recently read blocks of another
+/// image in memory.
+///
+/// Encrypted images and [`PfscImage`](crate::pfsc::PfscImage) decrypt or
+/// decompress a whole block for every read that touches it, so reading a file
+/// in small pieces (as [`FileReader`](crate::file::FileReader) does) decodes
+/// the same block many times. This adapter reads the inner image a block at a
+/// time and remembers the last `capacity` blocks, evicting the least recently
+/// used one. The cache is shared between threads behind a mutex, which is not
+/// held while the inner image is read.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::image::{CachedImage, Image};
+/// # use std::io;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// #
+/// # struct Counting(AtomicUsize);
+/// #
+/// # impl Image for Counting {
+/// #     fn read_at(&self, _: u64, buf: &mut [u8]) -> io::Result<usize> {
+/// #         self.0.fetch_add(1, Ordering::Relaxed);
+/// #         buf.fill(0xAB);
+/// #         Ok(buf.len())
+/// #     }
+/// #
+/// #     fn len(&self) -> u64 {
+/// #         0x10000
+/// #     }
+/// # }
+///
+/// let image = CachedImage::new(Counting(AtomicUsize::new(0)), 4);
+/// let mut buf = [0u8; 16];
+///
+/// // Many small reads within one block reach the inner image once.
+/// for offset in (0..0x1000).step_by(16) {
+///     image.read_exact_at(offset, &mut buf).unwrap();
+/// }
+///
+/// assert_eq!(image.into_inner().0.into_inner(), 1);
+/// ```
+pub struct CachedImage<I: Image> {
+    inner: I,
+    block_size: u64,
+    capacity: usize,
+    /// Cached blocks, least recently used first.
+    blocks: Mutex<VecDeque<(u64, Box<[u8]>)>>,
+}
+
+impl<I: Image> CachedImage<I> {
+    /// Wraps `inner`, caching up to `capacity` XTS-sized (4 KiB) blocks.
+    ///
+    /// A `capacity` of zero disables caching.
+    pub fn new(inner: I, capacity: usize) -> Self {
+        Self::with_block_size(inner, capacity, XTS_BLOCK_SIZE)
+    }
+
+    /// Wraps `inner`, caching up to `capacity` blocks of `block_size` bytes.
+    ///
+    /// Match `block_size` to the unit the inner image decodes, such as the
+    /// PFSC block size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn with_block_size(inner: I, capacity: usize, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must be non-zero");
+
+        Self {
+            inner,
+            block_size: block_size as u64,
+            capacity,
+            blocks: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Drops every cached block.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the wrapped image.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<(u64, Box<[u8]>)>> {
+        // The cache holds no invariants a panicking reader could break.
+        self.blocks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Copies from cached block `index` at `offset` into `buf`, returning the
+    /// number of bytes copied, or `None` if the block is not cached.
+    fn read_cached(&self, index: u64, offset: usize, buf: &mut [u8]) -> Option<usize> {
+        let mut blocks = self.lock();
+        let pos = blocks.iter().position(|(i, _)| *i == index)?;
+        let block = blocks.remove(pos).unwrap();
+        let n = min(buf.len(), block.1.len().saturating_sub(offset));
+
+        buf[..n].copy_from_slice(&block.1[offset..offset + n]);
+        blocks.push_back(block);
+
+        Some(n)
+    }
+
+    /// Reads block `index` from the inner image and caches it.
+    fn load(&self, index: u64) -> io::Result<Box<[u8]>> {
+        let start = index * self.block_size;
+        let len = min(self.block_size, self.inner.len().saturating_sub(start)) as usize;
+        let mut block = vec![0u8; len].into_boxed_slice();
+
+        self.inner.read_exact_at(start, &mut block)?;
+
+        let mut blocks = self.lock();
+
+        // Another thread may have loaded it meanwhile.
+        if !blocks.iter().any(|(i, _)| *i == index) {
+            if blocks.len() == self.capacity {
+                blocks.pop_front();
+            }
+
+            blocks.push_back((index, block.clone()));
+        }
+
+        Ok(block)
+    }
+}
+
+impl<I: Image> std::fmt::Debug for CachedImage<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedImage")
+            .field("block_size", &self.block_size)
+            .field("capacity", &self.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: Image> Image for CachedImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        if self.capacity == 0 {
+            return self.inner.read_at(offset, output_buf);
+        }
+
+        let len = self.inner.len();
+        let mut copied = 0;
+        let mut pos = offset;
+
+        while copied < output_buf.len() && pos < len {
+            let index = pos / self.block_size;
+            let offset_in_block = (pos % self.block_size) as usize;
+            let dst = &mut output_buf[copied..];
+
+            let n = match self.read_cached(index, offset_in_block, dst) {
+                Some(n) => n,
+                None => {
+                    let block = self.load(index)?;
+                    let n = min(dst.len(), block.len() - offset_in_block);
+
+                    dst[..n].copy_from_slice(&block[offset_in_block..offset_in_block + n]);
+                    n
+                }
+            };
+
+            if n == 0 {
+                break;
+            }
+
+            copied += n;
+            pos += n as u64;
+        }
+
+        Ok(copied)
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "CachedImage(block_size={}, capacity={}) over {}",
+            self.block_size,
+            self.capacity,
+            self.inner.describe()
+        )
+    }
+}
+
+/// Block size of [`PrefetchImage::new()`].
+const PREFETCH_BLOCK_SIZE: usize = 0x10000;
+
+/// An [`Image`] adapter that reads ahead of its readers on a background thread.
+///
+/// After every read, the `window` blocks following it are queued, and a
+/// worker thread reads them from the inner image into a bounded cache. A
+/// sequential reader, such as a worker extracting one file, then finds its
+/// next blocks already loaded, which hides the latency of network-backed or
+/// otherwise slow images. Reads that miss the cache go straight to the inner
+/// image; a read of the block being prefetched waits for it instead.
+///
+/// The cache holds up to `2 * window` blocks, evicting the oldest. With many
+/// concurrent readers, size `window` so every reader's read-ahead fits. The
+/// worker stops when the adapter is dropped.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::image::{Image, PrefetchImage};
+/// # use std::io;
+/// #
+/// # struct Pattern;
+/// #
+/// # impl Image for Pattern {
+/// #     fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+/// #         let n = buf.len().min((self.len() - offset.min(self.len())) as usize);
+/// #
+/// #         for (i, b) in buf[..n].iter_mut().enumerate() {
+/// #             *b = (offset as usize + i) as u8;
+/// #         }
+/// #
+/// #         Ok(n)
+/// #     }
+/// #
+/// #     fn len(&self) -> u64 {
+/// #         0x100000
+/// #     }
+/// # }
+///
+/// let image = PrefetchImage::new(Pattern, 4);
+/// let mut buf = vec![0u8; 0x8000];
+///
+/// for offset in (0..image.len()).step_by(buf.len()) {
+///     image.read_exact_at(offset, &mut buf).unwrap();
+///     assert_eq!(buf[1], (offset + 1) as u8);
+/// }
+/// ```
+pub struct PrefetchImage<I: Image + 'static> {
+    shared: Arc<PrefetchShared<I>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+/// State shared between a [`PrefetchImage`] and its worker.
+struct PrefetchShared<I> {
+    inner: I,
+    block_size: u64,
+    window: usize,
+    state: Mutex<PrefetchState>,
+    /// Signals queued blocks to the worker and loaded blocks to readers.
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct PrefetchState {
+    /// Blocks waiting to be read, oldest request first.
+    queue: VecDeque<u64>,
+    /// Prefetched blocks, oldest first.
+    blocks: VecDeque<(u64, Box<[u8]>)>,
+    /// Block the worker is reading.
+    loading: Option<u64>,
+    shutdown: bool,
+}
+
+impl<I: Image + 'static> PrefetchImage<I> {
+    /// Wraps `inner`, reading up to `window` 64 KiB blocks ahead of each read.
+    ///
+    /// A `window` of zero disables prefetching and starts no thread.
+    pub fn new(inner: I, window: usize) -> Self {
+        Self::with_block_size(inner, window, PREFETCH_BLOCK_SIZE)
+    }
+
+    /// Wraps `inner`, reading up to `window` blocks of `block_size` bytes
+    /// ahead of each read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn with_block_size(inner: I, window: usize, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must be non-zero");
+
+        let shared = Arc::new(PrefetchShared {
+            inner,
+            block_size: block_size as u64,
+            window,
+            state: Mutex::new(PrefetchState::default()),
+            changed: Condvar::new(),
+        });
+        let worker = (window > 0).then(|| {
+            let shared = shared.clone();
+            std::thread::spawn(move || shared.run())
+        });
+
+        Self { shared, worker }
+    }
+
+    /// Stops the worker and returns the wrapped image.
+    pub fn into_inner(mut self) -> I {
+        self.stop();
+
+        let shared = self.shared.clone();
+        drop(self);
+
+        match Arc::try_unwrap(shared) {
+            Ok(v) => v.inner,
+            Err(_) => unreachable!("the worker has exited"),
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            self.shared.lock().shutdown = true;
+            self.shared.changed.notify_all();
+
+            // A panic in the worker only loses prefetched data.
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Clears [`PrefetchState::loading`] if the worker unwinds while reading, so
+/// readers waiting for the block fall back to reading it themselves instead of
+/// waiting forever.
+struct LoadingGuard<'a, I>(&'a PrefetchShared<I>);
+
+impl<I> Drop for LoadingGuard<'_, I> {
+    fn drop(&mut self) {
+        self.0.lock().loading = None;
+        self.0.changed.notify_all();
+    }
+}
+
+impl<I> PrefetchShared<I> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, PrefetchState> {
+        // The state holds no invariants a panicking thread could break.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<I: Image> PrefetchShared<I> {
+    /// Worker loop: reads queued blocks until shut down.
+    fn run(&self) {
+        let mut state = self.lock();
+
+        loop {
+            if state.shutdown {
+                break;
+            }
+
+            let Some(index) = state.queue.pop_front() else {
+                state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+                continue;
+            };
+
+            if state.blocks.iter().any(|(i, _)| *i == index) {
+                continue;
+            }
+
+            state.loading = Some(index);
+            drop(state);
+
+            // Errors are left for the reader, which retries on a miss.
+            let guard = LoadingGuard(self);
+            let block = self.load(index).ok();
+
+            std::mem::forget(guard);
+            state = self.lock();
+            state.loading = None;
+
+            if let Some(block) = block {
+                if state.blocks.len() >= 2 * self.window {
+                    state.blocks.pop_front();
+                }
+
+                state.blocks.push_back((index, block));
+            }
+
+            self.changed.notify_all();
+        }
+    }
+
+    /// Reads block `index` from the inner image.
+    fn load(&self, index: u64) -> io::Result<Box<[u8]>> {
+        let start = index * self.block_size;
+        let len = min(self.block_size, self.inner.len().saturating_sub(start)) as usize;
+        let mut block = vec![0u8; len].into_boxed_slice();
+
+        self.inner.read_exact_at(start, &mut block)?;
+
+        Ok(block)
+    }
+
+    /// Copies from block `index` at `offset` into `buf` if it is prefetched,
+    /// waiting for it if the worker is reading it. Returns the number of bytes
+    /// copied, or `None` if the block is not available.
+    fn read_prefetched(&self, index: u64, offset: usize, buf: &mut [u8]) -> Option<usize> {
+        let mut state = self.lock();
+
+        while state.loading == Some(index) {
+            state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+
+        let (_, block) = state.blocks.iter().find(|(i, _)| *i == index)?;
+        let n = min(buf.len(), block.len().saturating_sub(offset));
+
+        buf[..n].copy_from_slice(&block[offset..offset + n]);
+
+        Some(n)
+    }
+
+    /// Queues the blocks following `end` that are not loaded or queued yet.
+    fn schedule(&self, end: u64) {
+        let first = end.div_ceil(self.block_size);
+        let last = min(
+            first + self.window as u64,
+            self.inner.len().div_ceil(self.block_size),
+        );
+        let mut state = self.lock();
+        let mut queued = false;
+
+        for index in first..last {
+            let known = state.loading == Some(index)
+                || state.queue.contains(&index)
+                || state.blocks.iter().any(|(i, _)| *i == index);
+
+            if !known {
+                state.queue.push_back(index);
+                queued = true;
+            }
+        }
+
+        // Drop the stalest requests rather than fall further behind.
+        while state.queue.len() > 2 * self.window {
+            state.queue.pop_front();
+        }
+
+        if queued {
+            self.changed.notify_all();
+        }
+    }
+}
+
+impl<I: Image + 'static> Drop for PrefetchImage<I> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl<I: Image + 'static> std::fmt::Debug for PrefetchImage<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrefetchImage")
+            .field("block_size", &self.shared.block_size)
+            .field("window", &self.shared.window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: Image + 'static> Image for PrefetchImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        let shared = &*self.shared;
+
+        if self.worker.is_none() {
+            return shared.inner.read_at(offset, output_buf);
+        }
+
+        let len = shared.inner.len();
+        let mut copied = 0;
+        let mut pos = offset;
+
+        while copied < output_buf.len() && pos < len {
+            let index = pos / shared.block_size;
+            let offset_in_block = (pos % shared.block_size) as usize;
+            let dst = &mut output_buf[copied..];
+
+            let n = match shared.read_prefetched(index, offset_in_block, dst) {
+                Some(n) => n,
+                // Read the rest directly; the worker only helps later reads.
+                None => shared.inner.read_at(pos, dst)?,
+            };
+
+            if n == 0 {
+                break;
+            }
+
+            copied += n;
+            pos += n as u64;
+        }
+
+        shared.schedule(pos);
+
+        Ok(copied)
+    }
+
+    fn len(&self) -> u64 {
+        self.shared.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "PrefetchImage(block_size={}, window={}) over {}",
+            self.shared.block_size,
+            self.shared.window,
+            self.shared.inner.describe()
+        )
+    }
+}
+
+/// Computes the SHA-256 of every `block_size`-byte block of `img`.
+///
+/// The last block may be shorter if the image length is not a multiple of
+/// `block_size`. Comparing the result for two images localizes where they
+/// differ.
+///
+/// # Panics
+///
+/// Panics if `block_size` is zero.
+pub fn hash_blocks<I: Image + ?Sized>(img: &I, block_size: usize) -> io::Result<Vec<[u8; 32]>> {
+    assert!(block_size > 0, "block size must be non-zero");
+
+    let len = img.len();
+    let mut hashes = Vec::with_capacity(len.div_ceil(block_size as u64) as usize);
+    let mut buf = vec![0u8; block_size];
+    let mut offset = 0u64;
+
+    while offset < len {
+        let n = min(block_size as u64, len - offset) as usize;
+
+        img.read_exact_at(offset, &mut buf[..n])?;
+        hashes.push(Sha256::digest(&buf[..n]).into());
+
+        offset += n as u64;
+    }
+
+    Ok(hashes)
 }
 
 /// Gets data key and tweak key from EKPFS and seed.
@@ -67,6 +1024,359 @@ pub(crate) fn get_xts_keys(ekpfs: &[u8], seed: &[u8; 16]) -> ([u8; 16], [u8; 16]
     (data_key, tweak_key)
 }
 
+/// Gets the block signing key from EKPFS and seed.
+pub(crate) fn get_sign_key(ekpfs: &[u8], seed: &[u8; 16]) -> [u8; 32] {
+    let mut hmac = Hmac::<Sha256>::new_from_slice(ekpfs).unwrap();
+    hmac.update(&[0x02, 0x00, 0x00, 0x00]);
+    hmac.update(seed);
+
+    hmac.finalize().into_bytes().into()
+}
+
+/// Computes the signature of a decrypted block.
+pub(crate) fn block_signature(sign_key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut hmac = Hmac::<Sha256>::new_from_slice(sign_key).unwrap();
+    hmac.update(data);
+
+    hmac.finalize().into_bytes().into()
+}
+
+/// PFS image backed by a [`std::fs::File`] using positional reads.
+///
+/// Reads go through `pread` on Unix and `seek_read` on Windows, so no memory
+/// map or lock is needed. This suits images larger than the address space,
+/// such as on 32-bit targets. The length is captured when the image is
+/// created.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use orbis_pfs::image::FileImage;
+///
+/// let image = FileImage::open("image.pfs")?;
+/// let pfs = orbis_pfs::open_image(image)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(any(unix, windows))]
+#[derive(Debug)]
+pub struct FileImage {
+    file: std::fs::File,
+    len: u64,
+    readonly: bool,
+}
+
+#[cfg(any(unix, windows))]
+impl FileImage {
+    /// Wraps an open file.
+    ///
+    /// Writes through [`WritableImage`] are passed to the file, so they fail
+    /// only if the file itself was not opened for writing.
+    pub fn new(file: std::fs::File) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+
+        Ok(Self {
+            file,
+            len,
+            readonly: false,
+        })
+    }
+
+    /// Opens the file at `path` for reading.
+    ///
+    /// [`write_at()`](WritableImage::write_at) on the returned image fails with
+    /// [`io::ErrorKind::PermissionDenied`].
+    ///
+    /// ```
+    /// use orbis_pfs::image::{FileImage, WritableImage};
+    ///
+    /// let path = std::env::temp_dir().join("orbis-pfs-file-image-open.pfs");
+    /// std::fs::write(&path, [0; 16]).unwrap();
+    ///
+    /// let err = FileImage::open(&path).unwrap().write_at(0, &[1]).unwrap_err();
+    ///
+    /// assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    /// assert_eq!(std::fs::read(&path).unwrap(), [0; 16]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn open(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        Ok(Self {
+            readonly: true,
+            ..Self::new(std::fs::File::open(path)?)?
+        })
+    }
+
+    /// Opens the file at `path` for reading and writing.
+    ///
+    /// Use this to patch an image on disk through [`WritableImage`].
+    pub fn open_rw(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        Self::new(
+            std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)?,
+        )
+    }
+
+    /// Returns the underlying file.
+    #[must_use]
+    pub fn into_inner(self) -> std::fs::File {
+        self.file
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl Image for FileImage {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            #[cfg(unix)]
+            let result = std::os::unix::fs::FileExt::read_at(&self.file, output_buf, offset);
+            #[cfg(windows)]
+            let result = std::os::windows::fs::FileExt::seek_read(&self.file, output_buf, offset);
+
+            match result {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                v => return v,
+            }
+        }
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn describe(&self) -> String {
+        format!("FileImage(len={}, readonly={})", self.len, self.readonly)
+    }
+}
+
+#[cfg(any(unix, windows))]
+impl WritableImage for FileImage {
+    fn write_at(&self, offset: u64, data: &[u8]) -> io::Result<()> {
+        if self.readonly {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "image is opened read-only",
+            ));
+        }
+
+        if offset
+            .checked_add(data.len() as u64)
+            .is_none_or(|end| end > self.len)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "write past the end of image",
+            ));
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::FileExt::write_all_at(&self.file, data, offset)
+        }
+
+        #[cfg(windows)]
+        {
+            let mut written = 0;
+
+            while written < data.len() {
+                match std::os::windows::fs::FileExt::seek_write(
+                    &self.file,
+                    &data[written..],
+                    offset + written as u64,
+                ) {
+                    Ok(0) => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+                    Ok(n) => written += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// A [`WritableImage`] that refuses writes to images flagged read-only.
+///
+/// The read-only flag is read from the PFS header when the guard is created.
+/// If it is set, every [`write_at()`](WritableImage::write_at) fails with
+/// [`io::ErrorKind::PermissionDenied`] without touching the image. Reads are
+/// passed through. This keeps tools from modifying images that declare
+/// themselves immutable; write to the inner image directly to override it.
+///
+/// # Example
+///
+/// ```no_run
+/// use orbis_pfs::image::{FileImage, GuardedImage, WritableImage};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let image = GuardedImage::new(FileImage::open_rw("image.pfs")?)?;
+///
+/// if image.is_readonly() {
+///     eprintln!("image is read-only");
+/// } else {
+///     image.write_at(0x10000, &[0; 16])?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct GuardedImage<I: WritableImage> {
+    inner: I,
+    readonly: bool,
+}
+
+impl<I: WritableImage> GuardedImage<I> {
+    /// Wraps `inner`, reading the read-only flag from its header.
+    ///
+    /// Returns [`io::ErrorKind::InvalidData`] if the header does not parse.
+    pub fn new(inner: I) -> io::Result<Self> {
+        let mut header = [0u8; crate::header::HEADER_SIZE];
+
+        inner.read_exact_at(0, &mut header)?;
+
+        let readonly = crate::header::PfsHeader::from_bytes(&header)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .is_readonly();
+
+        Ok(Self { inner, readonly })
+    }
+
+    /// Returns `true` if the header flags the image as read-only.
+    #[must_use]
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Returns the wrapped image.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I: WritableImage> Image for GuardedImage<I> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read_at(offset, output_buf)
+    }
+
+    fn read_exact_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact_at(offset, output_buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "GuardedImage(readonly={}) over {}",
+            self.readonly,
+            self.inner.describe()
+        )
+    }
+}
+
+impl<I: WritableImage> WritableImage for GuardedImage<I> {
+    fn write_at(&self, offset: u64, data: &[u8]) -> io::Result<()> {
+        if self.readonly {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "image is flagged read-only",
+            ));
+        }
+
+        self.inner.write_at(offset, data)
+    }
+}
+
+/// An [`Image`] backed by a read-only memory map of a file.
+///
+/// Reads are slice indexing into the mapping, so the operating system pages
+/// data in on demand instead of the whole file being read up front. The image
+/// owns its mapping, which makes it `'static` and usable with
+/// [`open_image()`](crate::open_image). For an unencrypted image,
+/// [`open_slice()`](crate::open_slice) on [`as_slice()`](Self::as_slice) is
+/// faster still because file reads skip the [`Image`] layer.
+///
+/// Enabled with the `mmap` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use orbis_pfs::image::MmapImage;
+///
+/// // SAFETY: nothing modifies the file while it is mapped.
+/// let image = unsafe { MmapImage::open("image.pfs")? };
+/// let pfs = orbis_pfs::open_image(image)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct MmapImage {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MmapImage {
+    /// Wraps an existing memory map.
+    pub fn new(map: memmap2::Mmap) -> Self {
+        Self { map }
+    }
+
+    /// Maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the file is not modified or truncated while the
+    /// image is in use. See [`memmap2::Mmap::map()`].
+    pub unsafe fn open(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+
+        unsafe { Self::map(&file) }
+    }
+
+    /// Maps an open file.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure the file is not modified or truncated while the
+    /// image is in use. See [`memmap2::Mmap::map()`].
+    pub unsafe fn map(file: &std::fs::File) -> io::Result<Self> {
+        Ok(Self::new(unsafe { memmap2::Mmap::map(file)? }))
+    }
+
+    /// Returns the mapped bytes.
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Returns the underlying memory map.
+    #[must_use]
+    pub fn into_inner(self) -> memmap2::Mmap {
+        self.map
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl Image for MmapImage {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
+        UnencryptedSlice::new(&self.map).read_at(offset, output_buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.map.len() as u64
+    }
+
+    fn describe(&self) -> String {
+        format!("MmapImage(len={})", self.map.len())
+    }
+}
+
 /// Unencrypted PFS image backed by a byte slice.
 ///
 /// Reads are pure slice indexing — no locks, no allocation, no state.
@@ -99,29 +1409,95 @@ impl Image for UnencryptedSlice<'_> {
     fn len(&self) -> u64 {
         self.data.len() as u64
     }
+
+    fn describe(&self) -> String {
+        format!("UnencryptedSlice(len={})", self.data.len())
+    }
 }
 
-/// Encrypted PFS image backed by a byte slice.
-pub(crate) struct EncryptedSlice<'a> {
-    data: &'a [u8],
+/// Encrypted PFS image, decrypting the sectors of another image.
+pub(crate) struct EncryptedImage<I: Image> {
+    inner: I,
     decryptor: Xts128<Aes128>,
     /// XTS block index where encryption begins.
     encrypted_start: usize,
 }
 
-impl<'a> EncryptedSlice<'a> {
-    pub fn new(data: &'a [u8], decryptor: Xts128<Aes128>, encrypted_start: usize) -> Self {
+impl<I: Image> EncryptedImage<I> {
+    pub fn new(inner: I, decryptor: Xts128<Aes128>, encrypted_start: usize) -> Self {
         Self {
-            data,
+            inner,
             decryptor,
             encrypted_start,
         }
     }
+
+    /// Checks the plaintext header sector and the first encrypted sector.
+    ///
+    /// The first encrypted sector starts the inode table, so a correct key
+    /// yields a super-root inode that is a directory. The check is skipped if
+    /// the super-root lies beyond that sector.
+    pub(crate) fn verify_header_sector(&self) -> Result<(), DecryptError> {
+        let mut sector = vec![0u8; XTS_BLOCK_SIZE];
+        let sector_at = |index: usize, buf: &mut [u8]| {
+            self.inner
+                .read_exact_at((index * XTS_BLOCK_SIZE) as u64, buf)
+                .ok()
+                .context(decrypt_error::SectorOutOfBoundsSnafu { sector: index })?;
+
+            if index >= self.encrypted_start {
+                self.decryptor
+                    .decrypt_sector(buf, get_tweak_default(index as _));
+            }
+
+            Ok::<_, DecryptError>(())
+        };
+
+        sector_at(0, &mut sector)?;
+
+        let header = crate::parse_header_sector(&sector)?;
+        let super_root = header.super_root_inode();
+        let index = self.encrypted_start;
+
+        sector_at(index, &mut sector)?;
+
+        let reader = if header.mode().is_signed() {
+            Inode::from_raw32_signed
+        } else {
+            Inode::from_raw32_unsigned
+        };
+
+        let mut src = &sector[..];
+
+        for inode in 0..=super_root {
+            let parsed = match reader(inode, &mut src) {
+                Ok(v) => v,
+                Err(FromRawError::TooSmall) => break,
+                Err(source) => {
+                    return Err(DecryptError::ParseInodeFailed {
+                        sector: index,
+                        inode,
+                        source,
+                    });
+                }
+            };
+
+            if inode == super_root && !parsed.is_directory() {
+                return Err(DecryptError::ImplausibleSuperRoot {
+                    sector: index,
+                    inode,
+                    mode: parsed.mode(),
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
 
-impl Image for EncryptedSlice<'_> {
+impl<I: Image> Image for EncryptedImage<I> {
     fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> io::Result<usize> {
-        let len = self.data.len() as u64;
+        let len = self.inner.len();
 
         if output_buf.is_empty() || offset >= len {
             return Ok(0);
@@ -136,13 +1512,12 @@ impl Image for EncryptedSlice<'_> {
             let offset_in_block = (pos as usize) % XTS_BLOCK_SIZE;
             let block_start = block * XTS_BLOCK_SIZE;
 
-            // Copy XTS block from backing slice into scratch buffer.
-            let src = self
-                .data
-                .get(block_start..block_start + XTS_BLOCK_SIZE)
-                .ok_or_else(|| io::Error::other(format!("XTS block #{} out of bounds", block)))?;
-
-            scratch.copy_from_slice(src);
+            // Read the XTS block into the scratch buffer.
+            self.inner
+                .read_exact_at(block_start as u64, &mut scratch)
+                .map_err(|e| {
+                    io::Error::new(e.kind(), format!("cannot read XTS block #{block}: {e}"))
+                })?;
 
             // Decrypt if in encrypted region.
             if block >= self.encrypted_start {
@@ -166,6 +1541,14 @@ impl Image for EncryptedSlice<'_> {
     }
 
     fn len(&self) -> u64 {
-        self.data.len() as u64
+        self.inner.len()
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "EncryptedImage(start={}) over {}",
+            self.encrypted_start,
+            self.inner.describe()
+        )
     }
 }
@@ -1,3 +1,6 @@
+// The derive refers to the deprecated variant kept for compatibility.
+#![allow(deprecated)]
+
 use crate::image::Image;
 
 use snafu::{ResultExt, Snafu, ensure};
@@ -46,6 +49,15 @@ pub struct InodeRaw {
     pub blocks: U32,
 }
 
+/// Mask for the file type bits of an inode mode.
+pub(crate) const S_IFMT: u16 = 0xf000;
+
+/// File type bits of a directory.
+pub(crate) const S_IFDIR: u16 = 0x4000;
+
+/// File type bits of a symbolic link.
+pub(crate) const S_IFLNK: u16 = 0xa000;
+
 /// Errors when loading inode blocks.
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -56,8 +68,12 @@ pub enum LoadBlocksError {
     #[snafu(display("block #{block} does not exist"))]
     NotExists { block: u32 },
 
+    #[deprecated(note = "double indirect blocks are supported")]
     #[snafu(display("double indirect block is not supported for inode #{inode}"))]
     DoubleIndirectBlockNotSupported { inode: usize },
+
+    #[snafu(display("indirect blocks of inode #{inode} end before all of its blocks"))]
+    IndirectBlocksExhausted { inode: usize },
 }
 
 /// Contains information for an inode.
@@ -65,10 +81,8 @@ pub struct Inode {
     index: usize,
     raw: InodeRaw,
     direct_blocks: [u32; 12],
-    #[allow(dead_code)] // Reserved for future signature verification
     direct_sigs: [Option<[u8; 32]>; 12],
     indirect_blocks: [u32; 5],
-    #[allow(dead_code)] // Reserved for future signature verification
     indirect_sigs: [Option<[u8; 32]>; 5],
     /// Whether this inode uses signed (36-byte) indirect block entries.
     /// When `false`, indirect entries are plain 4-byte block pointers.
@@ -76,6 +90,12 @@ pub struct Inode {
 }
 
 impl Inode {
+    /// On-disk size of an inode with plain block pointers.
+    pub(crate) const RAW32_UNSIGNED_SIZE: usize = size_of::<InodeRaw>() + 68;
+
+    /// On-disk size of an inode with signed block pointers.
+    pub(crate) const RAW32_SIGNED_SIZE: usize = size_of::<InodeRaw>() + 612;
+
     fn blocks(&self) -> u32 {
         self.raw.blocks.get()
     }
@@ -104,6 +124,28 @@ impl Inode {
         None
     }
 
+    /// Checks that the size fits the number of blocks.
+    ///
+    /// A non-zero size needs at least one block, and the blocks may not hold
+    /// more than one block beyond the size. Fewer blocks than the size needs
+    /// are allowed, since compressed files store less than their size.
+    pub(crate) fn check_size(&self, block_size: u32) -> Result<(), FromRawError> {
+        let size = self.size();
+        let blocks = self.blocks();
+        let allocated = u64::from(blocks) * u64::from(block_size);
+
+        ensure!(
+            (size == 0 || blocks != 0) && allocated <= size.saturating_add(block_size.into()),
+            from_raw_error::InconsistentSnafu {
+                inode: self.index,
+                size,
+                blocks,
+            }
+        );
+
+        Ok(())
+    }
+
     pub(super) fn from_raw32_unsigned(index: usize, src: &mut &[u8]) -> Result<Self, FromRawError> {
         // Parse header directly from slice.
         let (raw, rest) = InodeRaw::read_from_prefix(src).map_err(|_| FromRawError::TooSmall)?;
@@ -189,6 +231,36 @@ impl Inode {
         &self,
         image: &dyn Image,
         block_size: u32,
+    ) -> Result<Vec<u32>, LoadBlocksError> {
+        self.walk_blocks(image, block_size, None)
+    }
+
+    /// Collects the signature of every block of a signed inode.
+    ///
+    /// Returns `(physical block, signature)` pairs for the data blocks and the
+    /// indirect blocks that point to them. Unsigned inodes, and inodes whose
+    /// blocks are marked contiguous, have no signatures.
+    pub(crate) fn load_block_signatures(
+        &self,
+        image: &dyn Image,
+        block_size: u32,
+    ) -> Result<Vec<(u32, [u8; 32])>, LoadBlocksError> {
+        let mut sigs = Vec::new();
+
+        if self.signed {
+            self.walk_blocks(image, block_size, Some(&mut sigs))?;
+        }
+
+        Ok(sigs)
+    }
+
+    /// Walks the block tree, returning the block map and pushing signatures
+    /// to `sigs` if given.
+    fn walk_blocks(
+        &self,
+        image: &dyn Image,
+        block_size: u32,
+        mut sigs: Option<&mut Vec<(u32, [u8; 32])>>,
     ) -> Result<Vec<u32>, LoadBlocksError> {
         let block_count = self.blocks() as usize;
         let mut blocks: Vec<u32> = Vec::with_capacity(block_count);
@@ -209,68 +281,88 @@ impl Inode {
         // Load direct pointers.
         for i in 0..12 {
             blocks.push(self.direct_blocks[i]);
+
+            if let (Some(sigs), Some(sig)) = (sigs.as_deref_mut(), self.direct_sigs[i]) {
+                sigs.push((self.direct_blocks[i], sig));
+            }
+
             if blocks.len() == block_count {
                 return Ok(blocks);
             }
         }
 
-        let bs = block_size as u64;
+        // Load indirect pointers. The tree under `indirect_blocks[n]` is
+        // `n + 1` levels deep: single indirect, double indirect and so on.
+        let mut scratch = vec![vec![0u8; block_size as usize]; self.indirect_blocks.len()];
 
-        // Load indirect 0.
-        let block_num = self.indirect_blocks[0];
-        let offset = (block_num as u64) * bs;
-
-        let mut block0 = vec![0; block_size as usize];
-
-        image
-            .read_exact_at(offset, &mut block0)
-            .context(ReadSnafu { block: block_num })?;
-
-        let mut data = block0.as_slice();
+        for (level, &block) in self.indirect_blocks.iter().enumerate() {
+            if let (Some(sigs), Some(sig)) = (sigs.as_deref_mut(), self.indirect_sigs[level]) {
+                sigs.push((block, sig));
+            }
 
-        while let Some(i) = self.read_indirect(&mut data) {
-            blocks.push(i);
-            if blocks.len() == block_count {
+            if self.load_indirect(
+                image,
+                block,
+                &mut scratch[..=level],
+                &mut blocks,
+                sigs.as_deref_mut(),
+                block_count,
+            )? {
                 return Ok(blocks);
             }
         }
 
-        // Load indirect 1 (double indirect).
-        let block_num = self.indirect_blocks[1];
-        let offset = (block_num as u64) * bs;
+        IndirectBlocksExhaustedSnafu { inode: self.index }.fail()
+    }
 
-        image
-            .read_exact_at(offset, &mut block0)
-            .context(ReadSnafu { block: block_num })?;
+    /// Appends the data blocks under indirect block `block` to `blocks`.
+    ///
+    /// `scratch` holds one block-sized buffer per remaining level of the
+    /// tree, so its length is the depth of `block`. Returns `true` once
+    /// `blocks` holds `count` entries.
+    fn load_indirect(
+        &self,
+        image: &dyn Image,
+        block: u32,
+        scratch: &mut [Vec<u8>],
+        blocks: &mut Vec<u32>,
+        mut sigs: Option<&mut Vec<(u32, [u8; 32])>>,
+        count: usize,
+    ) -> Result<bool, LoadBlocksError> {
+        let (buf, children) = scratch.split_first_mut().unwrap();
+        let offset = (block as u64) * (buf.len() as u64);
 
-        let mut block1 = vec![0; block_size as usize];
-        let mut data0 = block0.as_slice();
+        image
+            .read_exact_at(offset, buf)
+            .context(ReadSnafu { block })?;
 
-        while let Some(i) = self.read_indirect(&mut data0) {
-            let offset = (i as u64) * bs;
+        let mut data = buf.as_slice();
 
-            image
-                .read_exact_at(offset, &mut block1)
-                .context(ReadSnafu { block: block_num })?;
+        while let Some((i, sig)) = self.read_indirect(&mut data) {
+            if let (Some(sigs), Some(sig)) = (sigs.as_deref_mut(), sig) {
+                sigs.push((i, sig));
+            }
 
-            let mut data1 = block1.as_slice();
+            if children.is_empty() {
+                blocks.push(i);
 
-            while let Some(j) = self.read_indirect(&mut data1) {
-                blocks.push(j);
-                if blocks.len() == block_count {
-                    return Ok(blocks);
+                if blocks.len() == count {
+                    return Ok(true);
                 }
+            } else if self.load_indirect(image, i, children, blocks, sigs.as_deref_mut(), count)? {
+                return Ok(true);
             }
         }
 
-        DoubleIndirectBlockNotSupportedSnafu { inode: self.index }.fail()
+        Ok(false)
     }
 
-    /// Reads one indirect block pointer from `raw`, advancing past the entry.
+    /// Reads one indirect block pointer and its signature from `raw`,
+    /// advancing past the entry.
     ///
     /// For unsigned inodes the entry is a plain 4-byte LE u32.
     /// For signed inodes the entry is a 32-byte signature followed by a 4-byte LE u32.
-    fn read_indirect(&self, raw: &mut &[u8]) -> Option<u32> {
+    fn read_indirect(&self, raw: &mut &[u8]) -> Option<(u32, Option<[u8; 32]>)> {
         let (entry_size, value_offset) = if self.signed { (36, 32) } else { (4, 0) };
 
         if raw.len() < entry_size {
@@ -278,14 +370,26 @@ impl Inode {
         }
 
         let value = u32::from_le_bytes(raw[value_offset..value_offset + 4].try_into().unwrap());
+        let sig = self.signed.then(|| raw[..32].try_into().unwrap());
+
         *raw = &raw[entry_size..];
-        Some(value)
+        Some((value, sig))
     }
 
     pub fn mode(&self) -> u16 {
         self.raw.mode.get()
     }
 
+    /// Returns `true` if the mode marks this inode as a directory.
+    pub fn is_directory(&self) -> bool {
+        self.mode() & S_IFMT == S_IFDIR
+    }
+
+    /// Returns `true` if the mode marks this inode as a symbolic link.
+    pub fn is_symlink(&self) -> bool {
+        self.mode() & S_IFMT == S_IFLNK
+    }
+
     pub fn flags(&self) -> InodeFlags {
         InodeFlags(self.raw.flags.get())
     }
@@ -369,4 +473,11 @@ pub enum FromRawError {
     /// Input data was too small.
     #[snafu(display("data too small"))]
     TooSmall,
+    /// The size does not match the number of blocks.
+    #[snafu(display("inode #{inode} has a size of {size} bytes in {blocks} blocks"))]
+    Inconsistent {
+        inode: usize,
+        size: u64,
+        blocks: u32,
+    },
 }
@@ -11,6 +11,7 @@
 //! - Support for compressed files (PFSC format)
 //! - Thread-safe: all read operations use positional I/O (`read_at`) —
 //!   no shared mutable cursor, no locks in the read path
+//! - Async reads on tokio's blocking pool (`async` feature)
 //!
 //! # Example
 //!
@@ -35,21 +36,31 @@ use self::inode::Inode;
 use aes::Aes128;
 use aes::cipher::KeyInit;
 use snafu::{OptionExt, ResultExt, Snafu, ensure};
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
 use xts_mode::Xts128;
 
+#[cfg(feature = "async")]
+pub mod async_io;
 pub mod directory;
+#[cfg(feature = "test-util")]
+pub mod fault;
 pub mod file;
+#[cfg(feature = "test-util")]
+pub mod fixture;
 pub mod header;
 pub mod image;
 pub mod inode;
+pub mod manifest;
 pub mod pfsc;
 
 /// Shared errors for PFS open operations.
 ///
 /// These errors can occur in both [`open_slice()`] and [`open_image()`] during
-/// the common phase: validating the header, reading inodes, and precomputing
-/// block maps.
+/// the common phase: validating the header and reading inodes. Block maps that
+/// cannot be loaded do not fail the open; reading the inode reports
+/// [`inode::LoadBlocksError`] instead.
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
 pub enum OpenError {
@@ -64,14 +75,40 @@ pub enum OpenError {
 
     #[snafu(display("invalid super-root"))]
     InvalidSuperRoot,
+}
 
-    #[snafu(display("cannot load block map for inode #{inode}"))]
-    LoadBlockMapFailed {
-        inode: usize,
-        source: inode::LoadBlocksError,
+/// Errors for [`Pfs::lookup()`].
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum LookupError {
+    #[snafu(display("path contains an empty component"))]
+    EmptyComponent,
+
+    #[snafu(display("'{component}' not found"))]
+    NotFound { component: String },
+
+    #[snafu(display("cannot look up '{component}' in a file"))]
+    NotADirectory { component: String },
+
+    #[snafu(display("cannot open directory to look up '{component}'"))]
+    OpenDirectory {
+        component: String,
+        source: directory::OpenError,
     },
 }
 
+/// An inode whose block map failed to load, reported by
+/// [`OpenOptions::open_slice_verified()`] and [`OpenOptions::open_image_verified()`].
+#[derive(Debug, Snafu)]
+#[snafu(display("cannot load block map for inode #{inode}"))]
+pub struct BlockMapError {
+    /// Index of the inode.
+    pub inode: usize,
+    /// Why the block map could not be loaded.
+    pub source: inode::LoadBlocksError,
+}
+
 /// Errors for [`open_slice()`].
 #[derive(Debug, Snafu)]
 #[snafu(module)]
@@ -86,10 +123,86 @@ pub enum OpenSliceError {
     #[snafu(display("encryption required but no EKPFS is provided"))]
     EmptyEkpfs,
 
+    #[snafu(display("encryption required but the header key seed is empty"))]
+    MissingKeySeed,
+
+    #[snafu(display("cannot parse header or its backup ({primary})"))]
+    ReadBackupHeaderFailed {
+        primary: header::ReadError,
+        source: header::ReadError,
+    },
+
     #[snafu(transparent)]
     Open { source: OpenError },
 }
 
+/// Errors for [`verify_header_sector()`] and [`decrypt_image()`].
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum DecryptError {
+    #[snafu(display("sector #{sector} is out of bounds"))]
+    SectorOutOfBounds { sector: usize },
+
+    #[snafu(display(
+        "sector #0 does not hold a valid PFS header (version {version}, format {format})"
+    ))]
+    InvalidHeader {
+        version: u64,
+        format: u64,
+        source: header::ReadError,
+    },
+
+    #[snafu(display("image is not encrypted"))]
+    NotEncrypted,
+
+    #[snafu(display("cannot set up decryption"))]
+    SetupFailed { source: OpenSliceError },
+
+    #[snafu(display("cannot parse inode #{inode} in decrypted sector #{sector}"))]
+    ParseInodeFailed {
+        sector: usize,
+        inode: usize,
+        source: inode::FromRawError,
+    },
+
+    #[snafu(display(
+        "super-root inode #{inode} in decrypted sector #{sector} has mode {mode:#o}, not a directory (wrong key?)"
+    ))]
+    ImplausibleSuperRoot {
+        sector: usize,
+        inode: usize,
+        mode: u16,
+    },
+}
+
+/// Errors for [`Pfs::verify_file_integrity()`].
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum IntegrityError {
+    #[snafu(display("image is not signed"))]
+    NotSigned,
+
+    #[snafu(display("image was opened without an EKPFS to derive the signing key"))]
+    NoSigningKey,
+
+    #[snafu(display("inode #{inode} is not valid"))]
+    InvalidInode { inode: usize },
+
+    #[snafu(display("cannot load inode #{inode}"))]
+    LoadInodeFailed { inode: usize, source: OpenError },
+
+    #[snafu(display("cannot load block signatures"))]
+    LoadSignaturesFailed { source: inode::LoadBlocksError },
+
+    #[snafu(display("cannot read block #{block}"))]
+    ReadBlockFailed { block: u32, source: std::io::Error },
+
+    #[snafu(display("signature of block #{block} does not match"))]
+    SignatureMismatch { block: u32 },
+}
+
 /// Errors for [`open_image()`].
 #[derive(Debug, Snafu)]
 #[snafu(module)]
@@ -104,6 +217,9 @@ pub enum OpenImageError {
     #[snafu(display("unsupported mode: {mode}"))]
     UnsupportedMode { mode: Mode },
 
+    #[snafu(display("cannot set up decryption"))]
+    SetupDecryptionFailed { source: OpenSliceError },
+
     #[snafu(transparent)]
     Open { source: OpenError },
 }
@@ -113,24 +229,52 @@ pub enum OpenImageError {
 /// This type is `Send + Sync` and can be shared across threads via [`Arc`].
 /// All read operations use positional I/O, so concurrent reads from multiple
 /// threads do not require synchronization.
+///
+/// Reads go through a type-erased [`Image`](image::Image), except for
+/// unencrypted images opened with [`open_slice()`], whose file data is copied
+/// straight from the slice without dynamic dispatch. Prefer [`open_slice()`]
+/// over [`open_image()`] when the whole image is already in memory.
 #[must_use]
 pub struct Pfs<'a> {
     image: Box<dyn image::Image + 'a>,
-    inodes: Vec<Inode>,
-    /// Precomputed block maps: `block_maps[inode_index]` gives the
-    /// logical-block -> physical-block mapping for that inode.
-    block_maps: Vec<Vec<u32>>,
+    /// Parsed inodes, one slot per inode block. Filled at open time, or on
+    /// first access when opened with [`OpenOptions::lazy()`].
+    inodes: Vec<OnceLock<Vec<Inode>>>,
+    /// Number of inodes, capped to what the inode blocks can hold.
+    inode_count: usize,
+    /// Number of inodes in each inode block.
+    inodes_per_block: usize,
+    /// Block size to check inode sizes against, with
+    /// [`OpenOptions::strict_inodes()`].
+    strict_inodes: Option<u32>,
+    /// Block maps: `block_maps[inode_index]` gives the logical-block ->
+    /// physical-block mapping for that inode. Filled at open time, or on first
+    /// access when opened with [`OpenOptions::lazy()`].
+    block_maps: Vec<OnceLock<Vec<u32>>>,
     root: usize,
     block_size: u32,
+    backup_block: u32,
+    /// Whether the header flags the image as read-only.
+    readonly: bool,
+    /// Plaintext copies of directory blocks, keyed by physical block number.
+    /// Only populated with [`OpenOptions::preload_directories()`].
+    dir_blocks: HashMap<u32, Box<[u8]>>,
+    /// Mode flags from the header.
+    mode: Mode,
+    /// Key for block signatures, if the image was opened with an EKPFS.
+    sign_key: Option<[u8; 32]>,
     /// Backing data for unencrypted, slice-backed images (from [`open_slice()`]).
     /// Enables zero-copy file access via [`file::File::as_slice()`].
     data: Option<&'a [u8]>,
+    /// Inode to path map, built by the first [`Pfs::inode_path()`].
+    inode_paths: OnceLock<HashMap<usize, PathBuf>>,
 }
 
 // SAFETY: All fields are Send + Sync:
 // - Box<dyn Image + 'a>: Image requires Send + Sync
-// - Vec<Inode>: Inode contains only Copy/primitive types
-// - Vec<Vec<u32>>, usize, u32: trivially Send + Sync
+// - Vec<OnceLock<Vec<Inode>>>: Inode contains only Copy/primitive types
+// - Vec<OnceLock<Vec<u32>>>, HashMap<u32, Box<[u8]>>, usize, u32, bool, Mode, Option<[u8; 32]>,
+//   OnceLock<HashMap<usize, PathBuf>>: trivially Send + Sync
 // - Option<&'a [u8]>: &[u8] is Send + Sync
 unsafe impl Send for Pfs<'_> {}
 unsafe impl Sync for Pfs<'_> {}
@@ -138,9 +282,10 @@ unsafe impl Sync for Pfs<'_> {}
 impl<'a> std::fmt::Debug for Pfs<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Pfs")
-            .field("inode_count", &self.inodes.len())
+            .field("inode_count", &self.inode_count)
             .field("root", &self.root)
             .field("block_size", &self.block_size)
+            .field("mode", &self.mode)
             .field("slice_backed", &self.data.is_some())
             .finish_non_exhaustive()
     }
@@ -152,7 +297,7 @@ impl<'a> Pfs<'a> {
     /// This represents the total number of files and directories in the filesystem.
     #[must_use]
     pub fn inode_count(&self) -> usize {
-        self.inodes.len()
+        self.inode_count
     }
 
     /// Returns the root directory of this PFS.
@@ -175,7 +320,186 @@ impl<'a> Pfs<'a> {
     /// # }
     /// ```
     pub fn root(self: &Arc<Self>) -> Directory<'a> {
-        Directory::new(self.clone(), self.root)
+        Directory::new(self.clone(), self.root, None)
+    }
+
+    /// Resolves a slash-separated path starting at the super-root.
+    ///
+    /// Leading and trailing slashes are ignored, so `"/uroot/eboot.bin"` and
+    /// `"uroot/eboot.bin"` are equivalent; an empty path (or `"/"`) returns the
+    /// super-root itself. Each component is looked up with
+    /// [`Directory::find()`], so no directory listing is collected on the way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LookupError::NotFound`] naming the first component that does
+    /// not exist, [`LookupError::NotADirectory`] if a file is used as a
+    /// directory, and [`LookupError::EmptyComponent`] for paths like `a//b`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use orbis_pfs::directory::DirEntry;
+    ///
+    /// let data = std::fs::read("image.pfs")?;
+    /// let pfs = orbis_pfs::open_slice(&data, None)?;
+    ///
+    /// if let DirEntry::File(file) = pfs.lookup("/uroot/sce_sys/param.sfo")? {
+    ///     println!("param.sfo is {} bytes", file.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn lookup(self: &Arc<Self>, path: &str) -> Result<directory::DirEntry<'a>, LookupError> {
+        let path = path.trim_matches('/');
+        let mut entry = directory::DirEntry::Directory(self.root());
+
+        if path.is_empty() {
+            return Ok(entry);
+        }
+
+        for component in path.split('/') {
+            ensure!(!component.is_empty(), lookup_error::EmptyComponentSnafu);
+
+            let directory::DirEntry::Directory(dir) = entry else {
+                return lookup_error::NotADirectorySnafu { component }.fail();
+            };
+
+            entry = dir
+                .find(component.as_bytes())
+                .context(lookup_error::OpenDirectorySnafu { component })?
+                .context(lookup_error::NotFoundSnafu { component })?;
+        }
+
+        Ok(entry)
+    }
+
+    /// Returns a lazy depth-first iterator over every entry below the super-root.
+    ///
+    /// Each entry comes with its path relative to the super-root, such as
+    /// `uroot/sce_sys/param.sfo`. A directory is yielded before its contents,
+    /// and `.` and `..` are skipped. Names that are not valid UTF-8 are
+    /// converted lossily. If a directory cannot be read, its error is yielded
+    /// in place of its contents and the walk continues with its siblings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pfs::directory::DirEntry;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("image.pfs")?;
+    /// let pfs = orbis_pfs::open_slice(&data, None)?;
+    ///
+    /// for result in pfs.walk() {
+    ///     if let (path, DirEntry::File(file)) = result? {
+    ///         println!("{} {}", path.display(), file.len());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Directories are entered right after they are yielded, and each level
+    /// is in name order:
+    ///
+    /// ```
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// let mut builder = ImageBuilder::new();
+    /// let uroot = builder.dir(ImageBuilder::SUPER_ROOT, "uroot");
+    /// builder.file(ImageBuilder::SUPER_ROOT, "a", Vec::new());
+    /// builder.file(uroot, "eboot.bin", Vec::new());
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::open_slice(&data, None).unwrap();
+    /// let paths: Vec<_> = pfs.walk().map(|r| r.unwrap().0).collect();
+    ///
+    /// assert_eq!(paths, ["a", "uroot", "uroot/eboot.bin"].map(std::path::PathBuf::from));
+    /// ```
+    pub fn walk(self: &Arc<Self>) -> directory::Walk<'a> {
+        directory::Walk::new(self.root())
+    }
+
+    /// Walks the whole tree and returns metadata for every file and directory.
+    ///
+    /// Entries are listed depth-first, with each directory's children in raw
+    /// name order. The super-root itself is not included. A directory linked
+    /// more than once, such as a dirent pointing back at an ancestor, fails
+    /// with [`OpenError::DirectoryCycle`](directory::OpenError::DirectoryCycle).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("image.pfs")?;
+    /// let pfs = orbis_pfs::open_slice(&data, None)?;
+    ///
+    /// for entry in pfs.manifest()? {
+    ///     println!("{} {}", String::from_utf8_lossy(&entry.path), entry.size);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn manifest(
+        self: &Arc<Self>,
+    ) -> Result<Vec<manifest::FileManifestEntry>, directory::OpenError> {
+        let mut entries = Vec::new();
+
+        manifest::collect(
+            self,
+            &self.root(),
+            &mut b"/".to_vec(),
+            &mut HashSet::new(),
+            &mut entries,
+        )?;
+
+        Ok(entries)
+    }
+
+    /// Returns the path of `inode`, relative to the super-root.
+    ///
+    /// The first call walks the whole tree and keeps an inode to path map, so
+    /// later lookups are cheap. This is meant for diagnostics, such as naming
+    /// the file behind a bare inode number in an error. Returns `None` for the
+    /// super-root and for inodes not reachable from it; directories that
+    /// cannot be read or that close a cycle are skipped. An inode linked from
+    /// several directories gets the first path in walk order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let data = vec![];
+    /// let pfs = orbis_pfs::open_slice(&data, None)?;
+    ///
+    /// if let Some(path) = pfs.inode_path(42) {
+    ///     println!("inode #42 is {}", path.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn inode_path(self: &Arc<Self>, inode: usize) -> Option<PathBuf> {
+        let paths = self.inode_paths.get_or_init(|| {
+            let mut paths = HashMap::new();
+
+            for (path, entry) in self.walk().flatten() {
+                let index = match &entry {
+                    directory::DirEntry::Directory(d) => d.inode_index(),
+                    directory::DirEntry::File(f) | directory::DirEntry::Symlink(f) => {
+                        f.inode_index()
+                    }
+                };
+
+                paths.entry(index).or_insert(path);
+            }
+
+            paths
+        });
+
+        paths.get(&inode).cloned()
     }
 
     /// Returns the block size used by this PFS.
@@ -184,18 +508,179 @@ impl<'a> Pfs<'a> {
         self.block_size
     }
 
+    /// Returns the mode flags from the header.
+    ///
+    /// This tells which backend [`open_slice()`] chose, for example to
+    /// re-encrypt an image only if it was encrypted.
+    #[must_use]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns `true` if the image is encrypted.
+    ///
+    /// Shorthand for `self.mode().is_encrypted()`.
+    #[must_use]
+    pub fn is_encrypted(&self) -> bool {
+        self.mode.is_encrypted()
+    }
+
+    /// Returns `true` if the header flags the image as read-only.
+    ///
+    /// Tools that patch images should check this before writing; see
+    /// [`GuardedImage`](image::GuardedImage) for an image that enforces it.
+    #[must_use]
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Returns the block holding the backup superblock, or 0 if there is none.
+    ///
+    /// See [`open_slice_with_fallback()`] for how it is used.
+    #[must_use]
+    pub fn backup_block(&self) -> u32 {
+        self.backup_block
+    }
+
+    /// Reads the raw header from the start of the image.
+    ///
+    /// The header sector is never encrypted, so this returns the plaintext
+    /// header for encrypted and unencrypted images alike. Use it to read
+    /// fields this crate does not expose. If the PFS was opened from its
+    /// backup header, this still returns the primary header at offset 0.
+    pub fn raw_header_bytes(&self) -> std::io::Result<[u8; header::HEADER_SIZE]> {
+        let mut buf = [0u8; header::HEADER_SIZE];
+
+        self.image.read_exact_at(0, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Checks the signature of every block of `inode`, including the
+    /// indirect blocks that point to them.
+    ///
+    /// Signed images store an HMAC-SHA256 of each decrypted block next to the
+    /// pointer to it. The key is derived from the EKPFS, so this needs an
+    /// image opened with [`open_slice()`] and its EKPFS. Returns the first
+    /// mismatch, which indicates tampering or corruption. Inodes whose blocks
+    /// are marked contiguous carry no signatures and always pass.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let (data, ekpfs) = (vec![], vec![]);
+    /// let pfs = orbis_pfs::open_slice(&data, Some(&ekpfs))?;
+    ///
+    /// for entry in pfs.manifest()? {
+    ///     if let Err(e) = pfs.verify_file_integrity(entry.inode) {
+    ///         eprintln!("{}: {e}", String::from_utf8_lossy(&entry.path));
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_file_integrity(&self, inode: usize) -> Result<(), IntegrityError> {
+        ensure!(self.mode.is_signed(), integrity_error::NotSignedSnafu);
+
+        let sign_key = self.sign_key.context(integrity_error::NoSigningKeySnafu)?;
+
+        ensure!(
+            inode < self.inode_count,
+            integrity_error::InvalidInodeSnafu { inode }
+        );
+
+        let sigs = self
+            .load_inode(inode)
+            .context(integrity_error::LoadInodeFailedSnafu { inode })?
+            .load_block_signatures(self.image(), self.block_size)
+            .context(integrity_error::LoadSignaturesFailedSnafu)?;
+
+        let mut buf = vec![0; self.block_size as usize];
+
+        for (block, expected) in sigs {
+            self.image
+                .read_exact_at((block as u64) * (self.block_size as u64), &mut buf)
+                .context(integrity_error::ReadBlockFailedSnafu { block })?;
+
+            ensure!(
+                image::block_signature(&sign_key, &buf) == expected,
+                integrity_error::SignatureMismatchSnafu { block }
+            );
+        }
+
+        Ok(())
+    }
+
     // --- Internal accessors for File / Directory / PfsFileImage ---
 
     pub(crate) fn image(&self) -> &dyn image::Image {
         &*self.image
     }
 
+    /// Returns inode `index`, which must already be loaded.
+    ///
+    /// Every inode handed out as a [`File`](file::File) or
+    /// [`Directory`](directory::Directory) was loaded when its dirent was read,
+    /// or at open time for the super-root.
     pub(crate) fn inode(&self, index: usize) -> &Inode {
-        &self.inodes[index]
+        let inodes = self.inodes[index / self.inodes_per_block]
+            .get()
+            .expect("inode is loaded before it is handed out");
+
+        &inodes[index % self.inodes_per_block]
     }
 
-    pub(crate) fn block_map(&self, inode: usize) -> &[u32] {
-        &self.block_maps[inode]
+    /// Returns inode `index`, parsing its inode block first if needed.
+    ///
+    /// `index` must be less than [`inode_count()`](Self::inode_count).
+    pub(crate) fn load_inode(&self, index: usize) -> Result<&Inode, OpenError> {
+        let block = index / self.inodes_per_block;
+        let cell = &self.inodes[block];
+
+        if cell.get().is_none() {
+            let inodes = read_inode_block(
+                self.image(),
+                self.mode,
+                self.block_size,
+                block,
+                self.inodes_per_block,
+                self.inode_count,
+                self.strict_inodes,
+            )?;
+
+            // A concurrent loader may have won the race; both results are identical.
+            let _ = cell.set(inodes);
+        }
+
+        Ok(self.inode(index))
+    }
+
+    /// Reads a full directory block, from the preloaded copy if there is one.
+    pub(crate) fn read_dir_block(&self, block: u32, buf: &mut [u8]) -> std::io::Result<()> {
+        if let Some(data) = self.dir_blocks.get(&block) {
+            buf.copy_from_slice(data);
+            return Ok(());
+        }
+
+        let offset = (block as u64) * (self.block_size as u64);
+        self.image.read_exact_at(offset, buf)
+    }
+
+    /// Returns the block map of `inode`, loading it first if needed.
+    pub(crate) fn block_map(&self, inode: usize) -> Result<&[u32], inode::LoadBlocksError> {
+        let cell = &self.block_maps[inode];
+
+        if let Some(map) = cell.get() {
+            return Ok(map);
+        }
+
+        let map = self
+            .inode(inode)
+            .load_block_map(self.image(), self.block_size)?;
+
+        // A concurrent loader may have won the race; both results are identical.
+        Ok(cell.get_or_init(|| map))
     }
 }
 
@@ -221,6 +706,7 @@ impl<'a> Pfs<'a> {
 /// Returns an [`OpenSliceError`] if:
 /// - The image header is invalid
 /// - The image is encrypted but no key is provided
+/// - The image is encrypted but its header has an all-zero key seed
 /// - The block structure is invalid
 ///
 /// # Example
@@ -237,36 +723,166 @@ pub fn open_slice<'a>(
     data: &'a [u8],
     ekpfs: Option<&[u8]>,
 ) -> Result<Arc<Pfs<'a>>, OpenSliceError> {
-    // Parse header directly from the slice.
-    let header = PfsHeader::from_bytes(data).context(open_slice_error::ReadHeaderFailedSnafu)?;
+    OpenOptions::new().open_slice(data, ekpfs)
+}
 
-    // Build the appropriate Image backend and determine zero-copy backing data.
-    let (image, backing_data): (Box<dyn image::Image + 'a>, Option<&'a [u8]>) =
-        if header.mode().is_encrypted() {
-            ensure!(
-                (header.block_size() as usize) >= image::XTS_BLOCK_SIZE,
-                open_slice_error::EncryptionBlockSizeTooSmallSnafu
-            );
+/// Opens a PFS image from a byte slice, falling back to the backup superblock.
+///
+/// This behaves like [`open_slice()`] when the primary header is valid. If it
+/// cannot be parsed, the `nbackup` field of the damaged header is used to
+/// locate the backup superblock (at `nbackup * block_size`), and the image is
+/// opened with that header instead. The fallback only helps when the block
+/// size and `nbackup` fields of the primary header are still intact and the
+/// backup superblock is stored unencrypted.
+///
+/// # Errors
+///
+/// Returns [`OpenSliceError::ReadBackupHeaderFailed`] if neither header can be
+/// parsed, or any other [`OpenSliceError`] that [`open_slice()`] can return.
+pub fn open_slice_with_fallback<'a>(
+    data: &'a [u8],
+    ekpfs: Option<&[u8]>,
+) -> Result<Arc<Pfs<'a>>, OpenSliceError> {
+    OpenOptions::new().open_slice_with_fallback(data, ekpfs)
+}
 
-            let ekpfs_bytes = ekpfs.context(open_slice_error::EmptyEkpfsSnafu)?;
+/// Checks that an encrypted PFS image decrypts with `ekpfs`.
+///
+/// This is a diagnostic for images that fail to open with an opaque error. It
+/// parses the plaintext header sector, then decrypts the first encrypted
+/// sector and checks that the super-root inode it holds is a directory. The
+/// error names the sector that failed and what was found there, which tells a
+/// corrupt header apart from a wrong key.
+///
+/// # Errors
+///
+/// Returns [`DecryptError::InvalidHeader`] with the version and format found
+/// if the header does not parse, [`DecryptError::ImplausibleSuperRoot`] if the
+/// decrypted inode is not a directory (usually a wrong key), or another
+/// [`DecryptError`] if the image is unencrypted or too short.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("image.pfs")?;
+/// let ekpfs = std::fs::read("ekpfs.bin")?;
+///
+/// if let Err(e) = orbis_pfs::verify_header_sector(&data, &ekpfs) {
+///     eprintln!("cannot decrypt image: {e}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn verify_header_sector(data: &[u8], ekpfs: &[u8]) -> Result<(), DecryptError> {
+    let sector = data
+        .get(..image::XTS_BLOCK_SIZE)
+        .context(decrypt_error::SectorOutOfBoundsSnafu { sector: 0usize })?;
+    let header = parse_header_sector(sector)?;
 
-            let key_seed = header.key_seed();
-            let (data_key, tweak_key) = image::get_xts_keys(ekpfs_bytes, key_seed);
-            let cipher_1 = Aes128::new((&data_key).into());
-            let cipher_2 = Aes128::new((&tweak_key).into());
+    ensure!(
+        header.mode().is_encrypted(),
+        decrypt_error::NotEncryptedSnafu
+    );
 
-            let enc = image::EncryptedSlice::new(
-                data,
-                Xts128::<Aes128>::new(cipher_1, cipher_2),
-                (header.block_size() as usize) / image::XTS_BLOCK_SIZE,
-            );
+    encrypted_image(image::UnencryptedSlice::new(data), Some(ekpfs), &header)
+        .context(decrypt_error::SetupFailedSnafu)?
+        .verify_header_sector()
+}
 
-            (Box::new(enc), None)
-        } else {
-            (Box::new(image::UnencryptedSlice::new(data)), Some(data))
-        };
+/// Returns a view of an encrypted PFS image that decrypts it with `ekpfs`.
+///
+/// The header sector is read as is and every other sector is decrypted as it
+/// is read, so the result can be dumped with
+/// [`Image::dump_to()`](image::Image::dump_to) to get a plaintext copy for
+/// external tools. The header of that copy still has the encrypted mode flag
+/// set. The key is not checked; use [`verify_header_sector()`] for that.
+///
+/// # Errors
+///
+/// Returns [`DecryptError::NotEncrypted`] if the image is not encrypted,
+/// [`DecryptError::InvalidHeader`] if the header does not parse, or another
+/// [`DecryptError`] if the image is too short or `ekpfs` cannot be used.
+///
+/// # Example
+///
+/// ```no_run
+/// use orbis_pfs::image::{FileImage, Image};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ekpfs = std::fs::read("ekpfs.bin")?;
+/// let image = orbis_pfs::decrypt_image(FileImage::open("image.pfs")?, &ekpfs)?;
+/// let mut out = std::fs::File::create("image.dec.pfs")?;
+///
+/// image.dump_to(&mut out, 1 << 20)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn decrypt_image<'a>(
+    image: impl image::Image + 'a,
+    ekpfs: &[u8],
+) -> Result<impl image::Image + 'a, DecryptError> {
+    let mut sector = vec![0u8; image::XTS_BLOCK_SIZE];
+
+    image
+        .read_exact_at(0, &mut sector)
+        .ok()
+        .context(decrypt_error::SectorOutOfBoundsSnafu { sector: 0usize })?;
+
+    let header = parse_header_sector(&sector)?;
+
+    ensure!(
+        header.mode().is_encrypted(),
+        decrypt_error::NotEncryptedSnafu
+    );
+
+    encrypted_image(image, Some(ekpfs), &header).context(decrypt_error::SetupFailedSnafu)
+}
+
+/// Parses the header in the plaintext header sector for diagnostics.
+fn parse_header_sector(sector: &[u8]) -> Result<PfsHeader, DecryptError> {
+    PfsHeader::from_bytes(sector).map_err(|source| {
+        let field = |i: usize| u64::from_le_bytes(sector[(i * 8)..(i * 8 + 8)].try_into().unwrap());
+
+        DecryptError::InvalidHeader {
+            version: field(0),
+            format: field(1),
+            source,
+        }
+    })
+}
+
+/// Sets up XTS decryption of `data` as described by `header`.
+fn encrypted_image<I: image::Image>(
+    image: I,
+    ekpfs: Option<&[u8]>,
+    header: &PfsHeader,
+) -> Result<image::EncryptedImage<I>, OpenSliceError> {
+    ensure!(
+        (header.block_size() as usize) >= image::XTS_BLOCK_SIZE,
+        open_slice_error::EncryptionBlockSizeTooSmallSnafu
+    );
 
-    Ok(open_inner(image, &header, backing_data)?)
+    let ekpfs_bytes = ekpfs
+        .filter(|k| !k.is_empty())
+        .context(open_slice_error::EmptyEkpfsSnafu)?;
+
+    // An unpopulated seed derives a useless key; report it instead of garbage data.
+    let key_seed = header.key_seed();
+    ensure!(
+        key_seed.iter().any(|&b| b != 0),
+        open_slice_error::MissingKeySeedSnafu
+    );
+
+    let (data_key, tweak_key) = image::get_xts_keys(ekpfs_bytes, key_seed);
+    let cipher_1 = Aes128::new((&data_key).into());
+    let cipher_2 = Aes128::new((&tweak_key).into());
+
+    Ok(image::EncryptedImage::new(
+        image,
+        Xts128::<Aes128>::new(cipher_1, cipher_2),
+        (header.block_size() as usize) / image::XTS_BLOCK_SIZE,
+    ))
 }
 
 /// Opens a PFS image for reading from any [`Image`](image::Image) implementation.
@@ -299,35 +915,357 @@ pub fn open_slice<'a>(
 /// # }
 /// ```
 pub fn open_image<'a>(image: impl image::Image + 'a) -> Result<Arc<Pfs<'a>>, OpenImageError> {
-    // Read header via positional read.
-    let mut header_buf = [0u8; header::HEADER_SIZE];
+    OpenOptions::new().open_image(image)
+}
 
-    image
-        .read_exact_at(0, &mut header_buf)
-        .context(open_image_error::ReadHeaderIoFailedSnafu)?;
+/// Opens a PFS image from any [`Image`](image::Image), decrypting it with
+/// `ekpfs` if it is encrypted.
+///
+/// This is [`open_image()`] for images that are not in memory as a whole, such
+/// as the outer PFS image of a PKG read from a stream. Encrypted data is read
+/// and decrypted one XTS sector at a time.
+///
+/// # Errors
+///
+/// Returns [`OpenImageError::UnsupportedMode`] if the image is encrypted and
+/// `ekpfs` is `None`, [`OpenImageError::SetupDecryptionFailed`] if the key or
+/// header cannot be used for decryption, or any other [`OpenImageError`] that
+/// [`open_image()`] can return.
+///
+/// # Example
+///
+/// ```no_run
+/// use orbis_pfs::image::FileImage;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ekpfs = std::fs::read("ekpfs.bin")?;
+/// let pfs = orbis_pfs::open_image_with_key(FileImage::open("image.pfs")?, Some(&ekpfs))?;
+/// println!("Opened PFS with {} inodes", pfs.inode_count());
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_image_with_key<'a>(
+    image: impl image::Image + 'a,
+    ekpfs: Option<&[u8]>,
+) -> Result<Arc<Pfs<'a>>, OpenImageError> {
+    OpenOptions::new().open_image_with_key(image, ekpfs)
+}
 
-    let header =
-        PfsHeader::from_bytes(&header_buf).context(open_image_error::ReadHeaderFailedSnafu)?;
+/// Options for opening a PFS image.
+///
+/// [`open_slice()`] and [`open_image()`] use the default options.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("image.pfs")?;
+/// let pfs = orbis_pfs::OpenOptions::new()
+///     .lazy(true)
+///     .open_slice(&data, None)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OpenOptions {
+    lazy: bool,
+    preload_directories: bool,
+    strict_inodes: bool,
+}
 
-    ensure!(
-        !header.mode().is_encrypted(),
-        open_image_error::UnsupportedModeSnafu {
-            mode: header.mode()
+impl OpenOptions {
+    /// Creates options with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defers parsing inodes and loading their block maps until first access.
+    ///
+    /// Only the super-root and the last block of the inode table are read at
+    /// open time. Every other inode block is parsed when a directory lists one
+    /// of its inodes, and the indirect blocks of an inode are read when it is
+    /// first read, which makes opening images with many inodes much cheaper.
+    /// An inode that cannot be parsed, including one rejected by
+    /// [`strict_inodes()`](Self::strict_inodes), then fails the
+    /// [`Directory::open()`](directory::Directory::open) that reaches it
+    /// instead of the open. [`preload_directories()`](Self::preload_directories)
+    /// still parses every inode to find the directories.
+    ///
+    /// ```
+    /// use orbis_pfs::directory::DirEntry;
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // Three blocks of inodes, with a file in the middle one that claims a
+    /// // block of data but has no blocks.
+    /// let mut builder = ImageBuilder::new();
+    /// let dir = builder.dir(ImageBuilder::SUPER_ROOT, "dir");
+    ///
+    /// for i in 0..60 {
+    ///     builder.file(dir, format!("{i}"), Vec::new());
+    /// }
+    ///
+    /// builder.size(30, 0x1000);
+    ///
+    /// let data = builder.build();
+    /// let pfs = orbis_pfs::OpenOptions::new()
+    ///     .lazy(true)
+    ///     .strict_inodes(true)
+    ///     .open_slice(&data, None)
+    ///     .unwrap();
+    ///
+    /// let mut root = pfs.root().open().unwrap();
+    /// let Some(DirEntry::Directory(dir)) = root.remove(b"dir") else {
+    ///     panic!("no directory");
+    /// };
+    ///
+    /// assert!(dir.open().is_err());
+    /// ```
+    pub fn lazy(&mut self, lazy: bool) -> &mut Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Reads every directory block once at open time and keeps it in memory.
+    ///
+    /// Subsequent [`Directory::open()`](directory::Directory::open) calls are
+    /// served from memory instead of decrypting or decompressing the same
+    /// blocks again, at the cost of holding all directory blocks. This pays
+    /// off when directories are opened repeatedly, such as resolving many
+    /// paths with [`Pfs::lookup()`]; a single walk reads each directory once
+    /// anyway and gains nothing.
+    ///
+    /// The open itself gets several times slower, since every directory block
+    /// is read up front. Run `cargo bench -p orbis-pfs --bench open` to
+    /// compare open, walk and lookup times on a synthetic encrypted image.
+    ///
+    /// A directory whose blocks cannot be loaded is not preloaded and does not
+    /// fail the open; opening that directory reports the error instead:
+    ///
+    /// ```
+    /// use orbis_pfs::directory::DirEntry;
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// let mut builder = ImageBuilder::new();
+    /// let broken = builder.dir(ImageBuilder::SUPER_ROOT, "broken");
+    /// let mut data = builder.build();
+    ///
+    /// // Give the directory an indirect block past the end of the image.
+    /// let inode = ImageBuilder::BLOCK_SIZE + broken * 168;
+    /// data[inode + 0x60..][..4].copy_from_slice(&13u32.to_le_bytes());
+    /// data[inode + 148..][..4].copy_from_slice(&u32::MAX.to_le_bytes());
+    ///
+    /// let pfs = orbis_pfs::OpenOptions::new()
+    ///     .preload_directories(true)
+    ///     .open_slice(&data, None)
+    ///     .unwrap();
+    /// let Some(DirEntry::Directory(dir)) = pfs.root().open().unwrap().remove(b"broken") else {
+    ///     panic!("no directory");
+    /// };
+    ///
+    /// assert!(dir.open().is_err());
+    /// ```
+    pub fn preload_directories(&mut self, preload: bool) -> &mut Self {
+        self.preload_directories = preload;
+        self
+    }
+
+    /// Fails the open on inodes whose size does not match their block count.
+    ///
+    /// An inode with a non-zero size but no blocks, or with more than one
+    /// block beyond its size, fails with [`OpenError::ParseInodeFailed`] and
+    /// [`inode::FromRawError::Inconsistent`]. This catches a corrupt inode
+    /// table at open time rather than on a later read, unless inodes are
+    /// parsed on first access with [`lazy()`](Self::lazy). It is off by
+    /// default, since such an inode may never be read.
+    ///
+    /// ```
+    /// use orbis_pfs::fixture::ImageBuilder;
+    ///
+    /// // The super-root claims a block of data but has no blocks.
+    /// let mut builder = ImageBuilder::new();
+    /// builder.size(ImageBuilder::SUPER_ROOT, 0x1000);
+    ///
+    /// let data = builder.build();
+    /// assert!(orbis_pfs::open_slice(&data, None).is_ok());
+    ///
+    /// let err = orbis_pfs::OpenOptions::new()
+    ///     .strict_inodes(true)
+    ///     .open_slice(&data, None)
+    ///     .unwrap_err();
+    ///
+    /// assert!(matches!(
+    ///     std::error::Error::source(&err)
+    ///         .and_then(|e| e.downcast_ref::<orbis_pfs::inode::FromRawError>()),
+    ///     Some(orbis_pfs::inode::FromRawError::Inconsistent { blocks: 0, .. })
+    /// ));
+    /// ```
+    pub fn strict_inodes(&mut self, strict: bool) -> &mut Self {
+        self.strict_inodes = strict;
+        self
+    }
+
+    /// Opens a PFS image from a byte slice with these options.
+    ///
+    /// See [`open_slice()`] for details.
+    pub fn open_slice<'a>(
+        &self,
+        data: &'a [u8],
+        ekpfs: Option<&[u8]>,
+    ) -> Result<Arc<Pfs<'a>>, OpenSliceError> {
+        // Parse header directly from the slice.
+        let header =
+            PfsHeader::from_bytes(data).context(open_slice_error::ReadHeaderFailedSnafu)?;
+
+        self.open_slice_with_header(data, ekpfs, &header)
+            .map(|(pfs, _)| pfs)
+    }
+
+    /// Opens a PFS image from a byte slice, checking every block map.
+    ///
+    /// All inodes and block maps are loaded at open time regardless of
+    /// [`lazy()`](Self::lazy).
+    /// Inodes whose block map cannot be loaded do not fail the open; they are
+    /// returned alongside the [`Pfs`] so a validation tool can report exactly
+    /// which files are broken while still reading the rest. Reading such an
+    /// inode fails with the same error.
+    pub fn open_slice_verified<'a>(
+        &self,
+        data: &'a [u8],
+        ekpfs: Option<&[u8]>,
+    ) -> Result<(Arc<Pfs<'a>>, Vec<BlockMapError>), OpenSliceError> {
+        let header =
+            PfsHeader::from_bytes(data).context(open_slice_error::ReadHeaderFailedSnafu)?;
+
+        self.eager().open_slice_with_header(data, ekpfs, &header)
+    }
+
+    /// Opens a PFS image from a byte slice with these options, falling back to
+    /// the backup superblock.
+    ///
+    /// See [`open_slice_with_fallback()`] for details.
+    pub fn open_slice_with_fallback<'a>(
+        &self,
+        data: &'a [u8],
+        ekpfs: Option<&[u8]>,
+    ) -> Result<Arc<Pfs<'a>>, OpenSliceError> {
+        let header = match PfsHeader::from_bytes(data) {
+            Ok(v) => v,
+            Err(primary) => PfsHeader::from_backup(data)
+                .context(open_slice_error::ReadBackupHeaderFailedSnafu { primary })?,
+        };
+
+        self.open_slice_with_header(data, ekpfs, &header)
+            .map(|(pfs, _)| pfs)
+    }
+
+    fn open_slice_with_header<'a>(
+        &self,
+        data: &'a [u8],
+        ekpfs: Option<&[u8]>,
+        header: &PfsHeader,
+    ) -> Result<(Arc<Pfs<'a>>, Vec<BlockMapError>), OpenSliceError> {
+        // Build the appropriate Image backend and determine zero-copy backing data.
+        let (image, backing_data): (Box<dyn image::Image + 'a>, Option<&'a [u8]>) =
+            if header.mode().is_encrypted() {
+                let image = image::UnencryptedSlice::new(data);
+
+                (Box::new(encrypted_image(image, ekpfs, header)?), None)
+            } else {
+                (Box::new(image::UnencryptedSlice::new(data)), Some(data))
+            };
+
+        let sign_key = ekpfs
+            .filter(|_| header.mode().is_signed())
+            .map(|k| image::get_sign_key(k, header.key_seed()));
+
+        Ok(open_inner(image, header, backing_data, sign_key, self)?)
+    }
+
+    /// Opens a PFS image from any [`Image`](image::Image) with these options.
+    ///
+    /// See [`open_image()`] for details.
+    pub fn open_image<'a>(
+        &self,
+        image: impl image::Image + 'a,
+    ) -> Result<Arc<Pfs<'a>>, OpenImageError> {
+        self.open_image_inner(image, None).map(|(pfs, _)| pfs)
+    }
+
+    /// Opens a possibly encrypted PFS image from any [`Image`](image::Image)
+    /// with these options.
+    ///
+    /// See [`open_image_with_key()`] for details.
+    pub fn open_image_with_key<'a>(
+        &self,
+        image: impl image::Image + 'a,
+        ekpfs: Option<&[u8]>,
+    ) -> Result<Arc<Pfs<'a>>, OpenImageError> {
+        self.open_image_inner(image, ekpfs).map(|(pfs, _)| pfs)
+    }
+
+    /// Opens a PFS image from any [`Image`](image::Image), checking every block map.
+    ///
+    /// See [`open_slice_verified()`](Self::open_slice_verified) for details.
+    pub fn open_image_verified<'a>(
+        &self,
+        image: impl image::Image + 'a,
+    ) -> Result<(Arc<Pfs<'a>>, Vec<BlockMapError>), OpenImageError> {
+        self.eager().open_image_inner(image, None)
+    }
+
+    /// Returns a copy of these options with lazy loading disabled.
+    fn eager(&self) -> Self {
+        Self {
+            lazy: false,
+            ..self.clone()
         }
-    );
+    }
+
+    fn open_image_inner<'a>(
+        &self,
+        image: impl image::Image + 'a,
+        ekpfs: Option<&[u8]>,
+    ) -> Result<(Arc<Pfs<'a>>, Vec<BlockMapError>), OpenImageError> {
+        // Read header via positional read.
+        let mut header_buf = [0u8; header::HEADER_SIZE];
+
+        image
+            .read_exact_at(0, &mut header_buf)
+            .context(open_image_error::ReadHeaderIoFailedSnafu)?;
+
+        let header =
+            PfsHeader::from_bytes(&header_buf).context(open_image_error::ReadHeaderFailedSnafu)?;
 
-    Ok(open_inner(Box::new(image), &header, None)?)
+        if !header.mode().is_encrypted() {
+            return Ok(open_inner(Box::new(image), &header, None, None, self)?);
+        }
+
+        let ekpfs = ekpfs.context(open_image_error::UnsupportedModeSnafu {
+            mode: header.mode(),
+        })?;
+        let image = encrypted_image(image, Some(ekpfs), &header)
+            .context(open_image_error::SetupDecryptionFailedSnafu)?;
+        let sign_key = header
+            .mode()
+            .is_signed()
+            .then(|| image::get_sign_key(ekpfs, header.key_seed()));
+
+        Ok(open_inner(Box::new(image), &header, None, sign_key, self)?)
+    }
 }
 
 /// Shared implementation for [`open_slice()`] and [`open_image()`].
 ///
-/// Validates the header fields, reads inodes, precomputes block maps, and
-/// constructs the [`Pfs`].
+/// Validates the header fields, constructs the [`Pfs`], and parses all inodes
+/// and precomputes their block maps (unless lazy).
 fn open_inner<'a>(
     image: Box<dyn image::Image + 'a>,
     header: &PfsHeader,
     data: Option<&'a [u8]>,
-) -> Result<Arc<Pfs<'a>>, OpenError> {
+    sign_key: Option<[u8; 32]>,
+    options: &OpenOptions,
+) -> Result<(Arc<Pfs<'a>>, Vec<BlockMapError>), OpenError> {
     let mode = header.mode();
     let block_size = header.block_size();
     let inode_count = header.inode_count();
@@ -339,84 +1277,175 @@ fn open_inner<'a>(
         InvalidBlockSizeSnafu
     );
 
-    // Read and parse all inodes.
-    let mut inodes: Vec<Inode> = Vec::with_capacity(inode_count);
-    let mut block_buf = vec![0; block_size as usize];
-
-    for block_num in 0..inode_block_count {
-        let offset = (block_size as u64) + (block_num as u64) * (block_size as u64);
-
-        image
-            .read_exact_at(offset, &mut block_buf)
-            .context(ReadBlockFailedSnafu { block: block_num })?;
+    // Inodes never straddle a block, so the table is cut short if its blocks
+    // cannot hold the declared number of inodes.
+    let inode_size = if mode.is_signed() {
+        Inode::RAW32_SIGNED_SIZE
+    } else {
+        Inode::RAW32_UNSIGNED_SIZE
+    };
+    let inodes_per_block = block_size as usize / inode_size;
+    let inode_count = inode_count.min(inodes_per_block.saturating_mul(inode_block_count as usize));
+    let strict_inodes = options.strict_inodes.then_some(block_size);
 
-        if parse_inodes_from_block(&block_buf, mode, &mut inodes, inode_count)? {
-            break;
-        }
-    }
+    ensure!(super_root < inode_count, InvalidSuperRootSnafu);
 
-    ensure!(super_root < inodes.len(), InvalidSuperRootSnafu);
+    // Check that the whole table is readable before allocating a slot per block.
+    let inode_blocks = inode_count.div_ceil(inodes_per_block);
+    let last = read_inode_block(
+        image.as_ref(),
+        mode,
+        block_size,
+        inode_blocks - 1,
+        inodes_per_block,
+        inode_count,
+        strict_inodes,
+    )?;
+    let mut inodes: Vec<OnceLock<Vec<Inode>>> =
+        (0..inode_blocks).map(|_| OnceLock::new()).collect();
 
-    // Precompute block maps for all inodes.
-    let block_maps = precompute_block_maps(&inodes, image.as_ref(), block_size)?;
+    inodes[inode_blocks - 1] = OnceLock::from(last);
 
-    Ok(Arc::new(Pfs {
+    let mut pfs = Pfs {
         image,
         inodes,
-        block_maps,
+        inode_count,
+        inodes_per_block,
+        strict_inodes,
+        block_maps: (0..inode_count).map(|_| OnceLock::new()).collect(),
         root: super_root,
         block_size,
+        backup_block: header.backup_block(),
+        readonly: header.is_readonly(),
+        dir_blocks: HashMap::new(),
+        mode,
+        sign_key,
         data,
-    }))
+        inode_paths: OnceLock::new(),
+    };
+
+    // Parse the super-root so it can be handed out, and everything else
+    // unless inodes are parsed on first access.
+    pfs.load_inode(super_root)?;
+
+    let errors = if options.lazy {
+        Vec::new()
+    } else {
+        for inode in 0..inode_count {
+            pfs.load_inode(inode)?;
+        }
+
+        precompute_block_maps(&pfs)
+    };
+
+    if options.preload_directories {
+        pfs.dir_blocks = preload_directory_blocks(&pfs);
+    }
+
+    Ok((Arc::new(pfs), errors))
 }
 
-/// Precomputes block maps for all inodes.
-fn precompute_block_maps(
-    inodes: &[Inode],
-    image: &dyn image::Image,
-    block_size: u32,
-) -> Result<Vec<Vec<u32>>, OpenError> {
-    let mut maps = Vec::with_capacity(inodes.len());
+/// Reads the blocks of every directory inode into memory.
+///
+/// A directory whose inode, block map or blocks cannot be loaded is skipped,
+/// like an unreadable block map at open time, so the error is only reported
+/// when that directory is opened.
+fn preload_directory_blocks(pfs: &Pfs<'_>) -> HashMap<u32, Box<[u8]>> {
+    let mut blocks = HashMap::new();
 
-    for (i, inode) in inodes.iter().enumerate() {
-        let block_map = inode
-            .load_block_map(image, block_size)
-            .context(LoadBlockMapFailedSnafu { inode: i })?;
-        maps.push(block_map);
+    for i in 0..pfs.inode_count {
+        if !pfs.load_inode(i).is_ok_and(Inode::is_directory) {
+            continue;
+        }
+
+        let Ok(block_map) = pfs.block_map(i) else {
+            continue;
+        };
+
+        let mut loaded = Vec::with_capacity(block_map.len());
+
+        for &block in block_map {
+            if blocks.contains_key(&block) {
+                continue;
+            }
+
+            let mut data = vec![0; pfs.block_size as usize].into_boxed_slice();
+
+            if pfs.read_dir_block(block, &mut data).is_err() {
+                loaded.clear();
+                break;
+            }
+
+            loaded.push((block, data));
+        }
+
+        blocks.extend(loaded);
     }
 
-    Ok(maps)
+    blocks
 }
 
-/// Parses inodes from a single block of data.
+/// Precomputes block maps for all inodes.
 ///
-/// Returns `true` if all expected inodes have been parsed, `false` if more blocks are
-/// needed (the current block was exhausted before reaching `inode_count`).
-fn parse_inodes_from_block(
-    block_data: &[u8],
+/// A block map that fails to load is left empty rather than failing the open,
+/// so a corrupt inode that is never reached (e.g. an orphan) does not make the
+/// rest of the image unreadable. Accessing such an inode retries the load and
+/// reports the error then. The load errors are returned for reporting.
+fn precompute_block_maps(pfs: &Pfs<'_>) -> Vec<BlockMapError> {
+    (0..pfs.inode_count)
+        .filter_map(|inode| {
+            pfs.block_map(inode)
+                .err()
+                .map(|source| BlockMapError { inode, source })
+        })
+        .collect()
+}
+
+/// Reads and parses inode block `block` of the inode table.
+///
+/// The block holds `per_block` inodes, except the last one which holds the
+/// rest up to `inode_count`. If `check` holds the block size, each inode's
+/// size is checked against it.
+fn read_inode_block(
+    image: &dyn image::Image,
     mode: Mode,
-    inodes: &mut Vec<Inode>,
+    block_size: u32,
+    block: usize,
+    per_block: usize,
     inode_count: usize,
-) -> Result<bool, OpenError> {
+    check: Option<u32>,
+) -> Result<Vec<Inode>, OpenError> {
     let reader = if mode.is_signed() {
         Inode::from_raw32_signed
     } else {
         Inode::from_raw32_unsigned
     };
 
-    let mut src = block_data;
+    // The inode table starts right after the header block.
+    let offset = (block as u64 + 1).saturating_mul(block_size as u64);
+    let mut data = vec![0; block_size as usize];
 
-    while inodes.len() < inode_count {
-        let inode = match reader(inodes.len(), &mut src) {
-            Ok(v) => v,
-            Err(inode::FromRawError::TooSmall) => {
-                return Ok(false);
-            }
-            err => err.context(ParseInodeFailedSnafu)?,
-        };
+    image
+        .read_exact_at(offset, &mut data)
+        .context(ReadBlockFailedSnafu {
+            block: block as u32,
+        })?;
+
+    let first = block * per_block;
+    let mut src = data.as_slice();
+    let mut inodes = Vec::with_capacity(per_block.min(inode_count - first));
+
+    for index in first..inode_count.min(first + per_block) {
+        let inode = reader(index, &mut src).context(ParseInodeFailedSnafu)?;
+
+        if let Some(block_size) = check {
+            inode
+                .check_size(block_size)
+                .context(ParseInodeFailedSnafu)?;
+        }
 
         inodes.push(inode);
     }
 
-    Ok(true)
+    Ok(inodes)
 }
@@ -0,0 +1,82 @@
+//! Flat listing of every file and directory, produced by [`Pfs::manifest()`].
+//!
+//! [`Pfs::manifest()`]: crate::Pfs::manifest
+
+use crate::Pfs;
+use crate::directory::{DirEntry, Directory, OpenError};
+use std::collections::HashSet;
+
+/// Metadata of a single file or directory in a [`Pfs::manifest()`].
+///
+/// [`Pfs::manifest()`]: crate::Pfs::manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileManifestEntry {
+    /// Absolute path from the super-root, with `/` separators (e.g. `/uroot/eboot.bin`).
+    ///
+    /// Names are raw bytes and are not guaranteed to be valid UTF-8.
+    pub path: Vec<u8>,
+    /// Index of the inode.
+    pub inode: usize,
+    /// `true` if this entry is a directory.
+    pub is_directory: bool,
+    /// Raw inode mode, including the file type bits.
+    pub mode: u16,
+    /// Logical size in bytes.
+    pub size: u64,
+    /// Size in bytes as stored in the image.
+    pub compressed_size: u64,
+    /// Modification time in seconds since the Unix epoch.
+    pub mtime: u64,
+    /// Nanosecond part of the modification time.
+    pub mtime_nsec: u32,
+}
+
+/// Appends entries for everything below `dir` to `out`.
+///
+/// `path` is the path of `dir` with a trailing `/` and is restored on return.
+/// `visited` holds the inodes of the directories collected so far; reaching
+/// one of them again fails with [`OpenError::DirectoryCycle`].
+pub(crate) fn collect(
+    pfs: &Pfs<'_>,
+    dir: &Directory<'_>,
+    path: &mut Vec<u8>,
+    visited: &mut HashSet<usize>,
+    out: &mut Vec<FileManifestEntry>,
+) -> Result<(), OpenError> {
+    let inode = dir.inode_index();
+
+    if !visited.insert(inode) {
+        return Err(OpenError::DirectoryCycle { inode });
+    }
+
+    for (name, entry) in dir.open()? {
+        let len = path.len();
+        path.extend_from_slice(&name);
+
+        let (index, subdir) = match &entry {
+            DirEntry::Directory(d) => (d.inode_index(), Some(d)),
+            DirEntry::File(f) | DirEntry::Symlink(f) => (f.inode_index(), None),
+        };
+        let inode = pfs.inode(index);
+
+        out.push(FileManifestEntry {
+            path: path.clone(),
+            inode: index,
+            is_directory: subdir.is_some(),
+            mode: inode.mode(),
+            size: inode.size(),
+            compressed_size: inode.compressed_len(),
+            mtime: inode.mtime(),
+            mtime_nsec: inode.mtimensec(),
+        });
+
+        if let Some(subdir) = subdir {
+            path.push(b'/');
+            collect(pfs, subdir, path, visited, out)?;
+        }
+
+        path.truncate(len);
+    }
+
+    Ok(())
+}
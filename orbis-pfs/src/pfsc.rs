@@ -1,4 +1,4 @@
-use flate2::FlushDecompress;
+use flate2::{Compression, FlushCompress, FlushDecompress};
 use std::cmp::min;
 use std::io::{self, ErrorKind};
 use zerocopy::{
@@ -10,7 +10,7 @@ use crate::image::Image;
 use snafu::{Snafu, ensure};
 
 /// PFSC header (48 bytes).
-#[derive(Clone, Copy, FromBytes, KnownLayout, Immutable)]
+#[derive(Clone, Copy, FromBytes, IntoBytes, KnownLayout, Immutable)]
 #[repr(C)]
 struct PfscHeader {
     /// 0x00: Magic bytes "PFSC"
@@ -46,6 +46,9 @@ pub enum OpenError {
     #[snafu(display("invalid magic"))]
     InvalidMagic,
 
+    #[snafu(display("invalid original block size"))]
+    InvalidBlockSize,
+
     #[snafu(display("cannot read block mapping"))]
     ReadBlockMappingFailed { source: std::io::Error },
 }
@@ -57,6 +60,11 @@ pub enum OpenError {
 /// All state is local to each call — no shared mutable state, naturally
 /// thread-safe.
 ///
+/// The header carries two block sizes. The block offset table is indexed in
+/// units of the original (decompressed) block size, and every block
+/// decompresses to exactly that many bytes. The compressed block size is only
+/// informational and does not affect reads.
+///
 /// Created via [`PfscImage::open()`].
 ///
 /// # Example
@@ -71,6 +79,48 @@ pub enum OpenError {
 /// # Ok(())
 /// # }
 /// ```
+///
+/// A PFSC whose compressed and original block sizes differ:
+///
+/// ```
+/// use orbis_pfs::image::Image;
+/// use orbis_pfs::pfsc::PfscImage;
+///
+/// struct Mem(Vec<u8>);
+///
+/// impl Image for Mem {
+///     fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+///         let data = self.0.get(offset as usize..).unwrap_or(&[]);
+///         let n = buf.len().min(data.len());
+///         buf[..n].copy_from_slice(&data[..n]);
+///         Ok(n)
+///     }
+///
+///     fn len(&self) -> u64 {
+///         self.0.len() as u64
+///     }
+/// }
+///
+/// let mut data = vec![0u8; 0x200];
+/// data[0x00..0x04].copy_from_slice(b"PFSC");
+/// data[0x0C..0x10].copy_from_slice(&0x10000u32.to_le_bytes()); // compressed block size
+/// data[0x10..0x18].copy_from_slice(&0x100u64.to_le_bytes()); // original block size
+/// data[0x18..0x20].copy_from_slice(&0x30u64.to_le_bytes()); // block offset table
+/// data[0x28..0x30].copy_from_slice(&0x200u64.to_le_bytes()); // original length
+///
+/// // Block 0 is stored as is, block 1 is sparse.
+/// for (i, v) in [0x100u64, 0x200, 0x400].iter().enumerate() {
+///     data[0x30 + i * 8..0x38 + i * 8].copy_from_slice(&v.to_le_bytes());
+/// }
+/// data[0x100..0x200].fill(0xAB);
+///
+/// let pfsc = PfscImage::open(Mem(data)).unwrap();
+/// let mut out = vec![0xFFu8; 0x200];
+/// pfsc.read_exact_at(0, &mut out).unwrap();
+///
+/// assert!(out[..0x100].iter().all(|&b| b == 0xAB));
+/// assert!(out[0x100..].iter().all(|&b| b == 0));
+/// ```
 pub struct PfscImage<I: Image> {
     source: I,
     block_size: u32,
@@ -115,8 +165,11 @@ impl<I: Image> PfscImage<I> {
         let block_offsets_offset = header.block_offsets.get();
         let original_size = header.data_length.get();
 
-        // Read block offsets.
-        let original_block_count = original_size / original_block_size + 1;
+        ensure!(original_block_size != 0, InvalidBlockSizeSnafu);
+
+        // Read block offsets. Each block covers `original_block_size` decompressed bytes,
+        // and the table holds one extra entry for the end of the last block.
+        let original_block_count = original_size.div_ceil(original_block_size) + 1;
         let mut compressed_blocks: Vec<u64> = vec![0; original_block_count as usize];
 
         source
@@ -136,16 +189,29 @@ impl<I: Image> PfscImage<I> {
     }
 
     /// Returns the decompressed size of the file.
+    ///
+    /// This is the same as [`Image::len()`], which reports the size of the
+    /// decompressed view this adapter exposes.
     #[must_use]
     pub fn decompressed_len(&self) -> u64 {
         self.original_size
     }
 
+    /// Returns the end offset of the last compressed block in the source.
+    ///
+    /// This is the on-disk size of the PFSC stream, including its header and
+    /// block table. The source image itself may be larger if it has trailing
+    /// padding.
+    #[must_use]
+    pub fn compressed_len(&self) -> u64 {
+        self.compressed_blocks.last().copied().unwrap_or(0)
+    }
+
     /// Decompresses a single PFSC block into `out`.
     ///
-    /// `out` must be exactly `self.block_size` bytes.
+    /// `out` must be exactly `self.original_block_size` bytes.
     fn decompress_block(&self, num: u64, out: &mut [u8]) -> io::Result<()> {
-        debug_assert_eq!(out.len(), self.block_size as usize);
+        debug_assert_eq!(out.len() as u64, self.original_block_size);
 
         // Get compressed block range.
         let end = match self.compressed_blocks.get(num as usize + 1) {
@@ -201,10 +267,10 @@ impl<I: Image> Image for PfscImage<I> {
             return Ok(0);
         }
 
-        let block_size = self.block_size as u64;
+        let block_size = self.original_block_size;
         let mut copied = 0usize;
         let mut pos = offset;
-        let mut block_buf = vec![0u8; self.block_size as usize];
+        let mut block_buf = vec![0u8; block_size as usize];
 
         while copied < buf.len() && pos < self.original_size {
             // Determine which PFSC block and offset within it.
@@ -219,7 +285,7 @@ impl<I: Image> Image for PfscImage<I> {
             let valid_in_block = if block_end > self.original_size {
                 (self.original_size - block_index * block_size) as usize
             } else {
-                self.block_size as usize
+                block_size as usize
             };
 
             // Copy the relevant portion to the output buffer.
@@ -239,4 +305,166 @@ impl<I: Image> Image for PfscImage<I> {
     fn len(&self) -> u64 {
         self.original_size
     }
+
+    fn describe(&self) -> String {
+        format!(
+            "PfscImage(block_size={}) over {}",
+            self.original_block_size,
+            self.source.describe()
+        )
+    }
+}
+
+/// Produces PFSC streams that [`PfscImage`] can read.
+///
+/// Each block is deflated on its own and stored in whichever of the reader's
+/// three encodings fits:
+///
+/// - compressed, when deflate makes the block smaller;
+/// - stored as is, when it does not;
+/// - sparse, for all-zero blocks at the end of the stream.
+///
+/// A sparse block takes more than a block of room in the offset table, so an
+/// all-zero block followed by data is deflated instead, which is far smaller.
+/// Trailing zero blocks are written as sparse and take no room at all, so
+/// [`PfscImage::compressed_len()`] of such a stream is past its end.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pfs::image::Image;
+/// use orbis_pfs::pfsc::{PfscImage, PfscWriter};
+///
+/// struct Mem(Vec<u8>);
+///
+/// impl Image for Mem {
+///     fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+///         let data = self.0.get(offset as usize..).unwrap_or(&[]);
+///         let n = buf.len().min(data.len());
+///         buf[..n].copy_from_slice(&data[..n]);
+///         Ok(n)
+///     }
+///
+///     fn len(&self) -> u64 {
+///         self.0.len() as u64
+///     }
+/// }
+///
+/// // Compressible text, incompressible noise, zeros and a partial tail.
+/// let mut data = b"orbis".repeat(0x100);
+/// let mut x = 0x2545F491u32;
+/// data.extend((0..0x1000).map(|_| {
+///     x ^= x << 13;
+///     x ^= x >> 17;
+///     x ^= x << 5;
+///     x as u8
+/// }));
+/// data.extend([0u8; 0x1800]);
+/// data.extend(b"tail");
+///
+/// let stream = PfscWriter::compress(&data, 0x1000);
+/// let pfsc = PfscImage::open(Mem(stream)).unwrap();
+/// let mut out = vec![0u8; data.len()];
+///
+/// assert_eq!(pfsc.len(), data.len() as u64);
+/// pfsc.read_exact_at(0, &mut out).unwrap();
+/// assert_eq!(out, data);
+///
+/// // Trailing zeros are sparse.
+/// let zeros = PfscWriter::compress(&[0u8; 0x3000], 0x1000);
+/// let pfsc = PfscImage::open(Mem(zeros)).unwrap();
+/// let mut out = vec![0xFFu8; 0x3000];
+///
+/// pfsc.read_exact_at(0, &mut out).unwrap();
+/// assert!(out.iter().all(|&b| b == 0));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct PfscWriter;
+
+impl PfscWriter {
+    /// Compresses `data` into a PFSC stream of `block_size` blocks.
+    ///
+    /// The stream is the 48-byte header, the block offset table and the
+    /// blocks, in that order. The last block is padded with zeros to a full
+    /// block, as the reader expects.
+    ///
+    /// # Panics
+    ///
+    /// If `block_size` is zero.
+    #[must_use]
+    pub fn compress(data: &[u8], block_size: u32) -> Vec<u8> {
+        assert_ne!(block_size, 0, "PFSC block size cannot be zero");
+
+        let bs = block_size as usize;
+        let count = data.len().div_ceil(bs);
+        let table = size_of::<PfscHeader>() as u64;
+        let mut offsets = Vec::with_capacity(count + 1);
+        let mut body = Vec::new();
+        let mut block = vec![0u8; bs];
+        let mut compressed = Vec::with_capacity(bs);
+
+        // Blocks from here on are all zeros and need no data.
+        let sparse_from = data.iter().rposition(|&b| b != 0).map_or(0, |i| i / bs + 1);
+
+        for (i, chunk) in data.chunks(bs).enumerate() {
+            offsets.push(body.len() as u64);
+
+            if i >= sparse_from {
+                continue;
+            }
+
+            block[..chunk.len()].copy_from_slice(chunk);
+            block[chunk.len()..].fill(0);
+
+            // Deflate into at most one block; anything that does not fit is stored as is.
+            let mut deflate = flate2::Compress::new(Compression::default(), true);
+
+            compressed.clear();
+
+            match deflate.compress_vec(&block, &mut compressed, FlushCompress::Finish) {
+                Ok(flate2::Status::StreamEnd) if compressed.len() < bs => {
+                    body.extend_from_slice(&compressed)
+                }
+                _ => body.extend_from_slice(&block),
+            }
+        }
+
+        // Sparse blocks are recognized by a range longer than a block.
+        let mut end = body.len() as u64;
+
+        for offset in &mut offsets[sparse_from.min(count)..] {
+            *offset = end;
+            end += u64::from(block_size) + 1;
+        }
+
+        offsets.push(if sparse_from < count {
+            end
+        } else {
+            body.len() as u64
+        });
+
+        // Make the offsets absolute now that the table size is known.
+        let data_start = table + (offsets.len() * size_of::<u64>()) as u64;
+        let header = PfscHeader {
+            magic: *PFSC_MAGIC,
+            _unknown_04: U32::new(0),
+            _unknown_08: U32::new(0),
+            block_size: U32::new(block_size),
+            block_size2: U64::new(block_size.into()),
+            block_offsets: U64::new(table),
+            _unknown_20: U64::new(0),
+            data_length: U64::new(data.len() as u64),
+        };
+        let mut out = Vec::with_capacity(data_start as usize + body.len());
+
+        out.extend_from_slice(header.as_bytes());
+
+        for offset in offsets {
+            out.extend_from_slice(&(data_start + offset).to_le_bytes());
+        }
+
+        out.extend_from_slice(&body);
+        out
+    }
 }
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
@@ -11,36 +11,84 @@ pub struct Cli {
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
-    /// Extract a PKG file to a directory
-    Extract {
-        /// Path to the PKG file
+    /// Extract a PKG file, or every PKG file in a directory, to a directory
+    Extract(ExtractArgs),
+
+    /// Display information about a PKG file, or every PKG file in a directory
+    Info {
+        /// Path to the PKG file or a directory of PKG files
         #[arg(value_name = "PKG_FILE")]
         pkg_path: PathBuf,
 
-        /// Output directory (defaults to title id)
-        #[arg(short, long, value_name = "DIR")]
-        output: Option<PathBuf>,
-
-        /// Overwrite existing files
-        #[arg(short, long)]
-        force: bool,
-
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Only show packages with this title ID (e.g. CUSA03173)
+        #[arg(long, value_name = "TITLE_ID")]
+        title_id: Option<String>,
     },
 
-    /// Display information about a PKG file
-    Info {
+    /// List entries in a PKG file
+    List {
         /// Path to the PKG file
         #[arg(value_name = "PKG_FILE")]
         pkg_path: PathBuf,
     },
 
-    /// List entries in a PKG file
-    List {
+    /// Check the header, entry table, digest table and body digests of a PKG file
+    Verify {
         /// Path to the PKG file
         #[arg(value_name = "PKG_FILE")]
         pkg_path: PathBuf,
     },
+
+    /// Compare the headers and entry tables of two PKG files
+    Diff {
+        /// Path to the first PKG file
+        #[arg(value_name = "OLD_PKG")]
+        old_path: PathBuf,
+
+        /// Path to the second PKG file
+        #[arg(value_name = "NEW_PKG")]
+        new_path: PathBuf,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ExtractArgs {
+    /// Path to the PKG file or a directory of PKG files
+    #[arg(value_name = "PKG_FILE")]
+    pub pkg_path: PathBuf,
+
+    /// Output directory (defaults to title id); for a directory of PKG files,
+    /// each package is extracted to a subdirectory of it named after the file
+    #[arg(short, long, value_name = "DIR")]
+    pub output: Option<PathBuf>,
+
+    /// Name the default output directory from service id, title id, version,
+    /// and label
+    /// (ignored for a directory of PKG files)
+    #[arg(long, conflicts_with = "output")]
+    pub full_name: bool,
+
+    /// Overwrite existing files
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Suppress progress output
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Write a metadata.json describing the package to the output directory
+    #[arg(long)]
+    pub metadata: bool,
+
+    /// Only extract PFS files in this PlayGo chunk (repeatable)
+    #[arg(long = "chunk", value_name = "ID")]
+    pub chunks: Vec<u16>,
+
+    /// Only extract packages with this title ID (e.g. CUSA03173)
+    #[arg(long, value_name = "TITLE_ID")]
+    pub title_id: Option<String>,
+
+    /// Print what would be extracted as JSON instead of extracting
+    #[arg(long, conflicts_with = "chunks")]
+    pub dry_run: bool,
 }
@@ -35,34 +35,18 @@ pub enum ExtractError {
         source: std::io::Error,
     },
 
-    // PFS extraction errors
-    #[snafu(display("PKG does not contain a PFS image"))]
-    NoPfsImage,
-
-    #[snafu(display("cannot open outer PFS: {source}"))]
-    OpenOuterPfsFailed { source: orbis_pfs::OpenSliceError },
-
-    #[snafu(display("cannot open super-root on outer PFS: {source}"))]
-    OpenOuterSuperRootFailed {
-        source: orbis_pfs::directory::OpenError,
-    },
-
-    #[snafu(display("outer PFS does not contain uroot directory"))]
-    NoOuterUroot,
-
-    #[snafu(display("cannot open uroot on outer PFS: {source}"))]
-    OpenOuterUrootFailed {
-        source: orbis_pfs::directory::OpenError,
+    #[snafu(display("cannot resolve path {}: {source}", path.display()))]
+    ResolvePathFailed {
+        path: PathBuf,
+        source: std::io::Error,
     },
 
-    #[snafu(display("outer PFS does not contain pfs_image.dat"))]
-    NoInnerImage,
-
-    #[snafu(display("cannot create decompressor for inner PFS: {source}"))]
-    CreateDecompressorFailed { source: orbis_pfs::pfsc::OpenError },
+    #[snafu(display("{} resolves outside the output directory", path.display()))]
+    PathOutsideOutput { path: PathBuf },
 
+    // PFS extraction errors
     #[snafu(display("cannot open inner PFS: {source}"))]
-    OpenInnerPfsFailed { source: orbis_pfs::OpenImageError },
+    OpenInnerPfsFailed { source: crate::OpenInnerPfsError },
 
     #[snafu(display("cannot open super-root on inner PFS: {source}"))]
     OpenInnerSuperRootFailed {
@@ -78,9 +62,25 @@ pub enum ExtractError {
         source: orbis_pfs::directory::OpenError,
     },
 
+    #[snafu(display("cannot read inner PFS manifest: {source}"))]
+    ReadPfsManifestFailed {
+        source: orbis_pfs::directory::OpenError,
+    },
+
+    #[snafu(display("cannot read PlayGo chunks: {source}"))]
+    ReadPlayGoChunksFailed {
+        source: orbis_pkg::PlayGoChunksError,
+    },
+
+    #[snafu(display("PlayGo chunk #{id} does not exist"))]
+    UnknownPlayGoChunk { id: u16 },
+
     #[snafu(display("unsupported file name in PFS path: {path}"))]
     UnsupportedFileName { path: String },
 
+    #[snafu(display("unsafe file name in PFS path: {path}"))]
+    UnsafeName { path: String },
+
     #[snafu(display("cannot read {path} from PFS: {source}"))]
     ReadPfsFileFailed {
         path: String,
@@ -0,0 +1,140 @@
+//! The `metadata.json` sidecar written by
+//! [`PkgExtractor::with_metadata_sidecar()`](super::PkgExtractor::with_metadata_sidecar).
+
+use super::ExtractError;
+use orbis_pfs::manifest::FileManifestEntry;
+use orbis_pkg::Pkg;
+use std::fmt::Write;
+
+/// File name of the sidecar in the output directory.
+pub(super) const FILE_NAME: &str = "metadata.json";
+
+/// Renders the sidecar for `pkg`, with the inner PFS `manifest` if given.
+pub(super) fn render<R: AsRef<[u8]> + Sync>(
+    pkg: &Pkg<R>,
+    manifest: Option<&[FileManifestEntry]>,
+) -> Result<String, ExtractError> {
+    let header = pkg.header();
+    let content_id = header.content_id();
+    let mut out = String::from("{\n");
+
+    field(&mut out, "content_id", &string(content_id.as_str()));
+    field(&mut out, "service_id", &string(content_id.service_id()));
+    field(
+        &mut out,
+        "publisher_code",
+        &string(content_id.publisher_code()),
+    );
+    field(&mut out, "title_id", &string(content_id.title_id()));
+    field(&mut out, "version", &string(content_id.version()));
+    field(&mut out, "label", &string(content_id.label()));
+    field(&mut out, "content_type", &header.content_type().to_string());
+    field(
+        &mut out,
+        "content_type_name",
+        &string(header.content_type_name()),
+    );
+    field(
+        &mut out,
+        "content_flags",
+        &header.content_flags().bits().to_string(),
+    );
+    field(
+        &mut out,
+        "content_flags_names",
+        &string(&header.content_flags().to_string()),
+    );
+    field(&mut out, "drm_type", &header.drm_type().to_string());
+    field(&mut out, "drm_type_name", &string(header.drm_type_name()));
+    field(&mut out, "platform", &string(header.platform().name()));
+    field(&mut out, "pkg_type", &header.pkg_type().to_string());
+    field(&mut out, "pkg_size", &header.pkg_size().to_string());
+
+    // Entries.
+    let mut entries = Vec::new();
+
+    for result in pkg.entries() {
+        let (num, entry) =
+            result.map_err(|e| ExtractError::ReadEntryFailed { num: 0, source: e })?;
+        let path = pkg
+            .entry_path(&entry, "")
+            .map(|p| string(&p.to_string_lossy()))
+            .unwrap_or_else(|| "null".into());
+
+        entries.push(format!(
+            "{{\"index\": {}, \"id\": {}, \"path\": {}, \"size\": {}, \"encrypted\": {}, \"key_index\": {}}}",
+            num,
+            entry.id(),
+            path,
+            entry.data_size(),
+            entry.is_encrypted(),
+            entry.key_index()
+        ));
+    }
+
+    array(&mut out, "entries", &entries, manifest.is_none());
+
+    // PFS manifest.
+    if let Some(manifest) = manifest {
+        let files: Vec<String> = manifest
+            .iter()
+            .map(|e| {
+                format!(
+                    "{{\"path\": {}, \"inode\": {}, \"directory\": {}, \"mode\": {}, \"size\": {}, \"compressed_size\": {}, \"mtime\": {}, \"mtime_nsec\": {}}}",
+                    string(&String::from_utf8_lossy(&e.path)),
+                    e.inode,
+                    e.is_directory,
+                    e.mode,
+                    e.size,
+                    e.compressed_size,
+                    e.mtime,
+                    e.mtime_nsec
+                )
+            })
+            .collect();
+
+        array(&mut out, "pfs", &files, true);
+    }
+
+    out.push_str("}\n");
+
+    Ok(out)
+}
+
+/// Appends a `"name": value,` line to the top-level object.
+fn field(out: &mut String, name: &str, value: &str) {
+    writeln!(out, "  {}: {},", string(name), value).unwrap();
+}
+
+/// Appends an array of pre-rendered values to the top-level object.
+fn array(out: &mut String, name: &str, items: &[String], last: bool) {
+    writeln!(out, "  {}: [", string(name)).unwrap();
+
+    for (i, item) in items.iter().enumerate() {
+        let comma = if i + 1 < items.len() { "," } else { "" };
+        writeln!(out, "    {item}{comma}").unwrap();
+    }
+
+    out.push_str(if last { "  ]\n" } else { "  ],\n" });
+}
+
+/// Renders `s` as a JSON string literal.
+pub(super) fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
@@ -1,20 +1,26 @@
 mod error;
+mod metadata;
+mod plan;
 
 pub use self::error::ExtractError;
+pub use self::plan::{ExtractPlan, PlannedFile, PlannedFileKind};
 
 use crate::progress::ExtractProgress;
 use orbis_pfs::directory::DirEntry;
 use orbis_pkg::Pkg;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::fs::{File, OpenOptions, create_dir_all};
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions, create_dir, create_dir_all};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
 
 /// Extracts a PKG file to the specified output directory.
 pub struct PkgExtractor<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> {
     pkg: &'a Pkg<R>,
     progress: P,
     overwrite: bool,
+    skip_existing_complete: bool,
+    metadata_sidecar: bool,
 }
 
 impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
@@ -27,14 +33,44 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
             pkg,
             progress,
             overwrite,
+            skip_existing_complete: false,
+            metadata_sidecar: false,
         }
     }
 
+    /// Skips PFS files whose destination already exists with the expected size.
+    ///
+    /// This makes re-running an interrupted extraction fast without a journal.
+    /// A destination with a different size is treated as partial and extracted
+    /// again, replacing it even when `overwrite` is `false`. A symlink is kept
+    /// if it already points to the same target. Skipped files are reported
+    /// through [`ExtractProgress::pfs_file_skipped()`].
+    #[must_use]
+    pub fn skip_existing_complete(mut self, skip: bool) -> Self {
+        self.skip_existing_complete = skip;
+        self
+    }
+
+    /// Writes a `metadata.json` describing the package to the output root
+    /// after [`extract()`](Self::extract).
+    ///
+    /// The sidecar holds the header fields, content ID components, flags, the
+    /// entry list and a manifest of the inner PFS, so an extraction describes
+    /// itself without running `info` separately. An existing `metadata.json`
+    /// is only replaced when `overwrite` is `true`.
+    #[must_use]
+    pub fn with_metadata_sidecar(mut self, enabled: bool) -> Self {
+        self.metadata_sidecar = enabled;
+        self
+    }
+
     /// Extracts the entire PKG to the specified directory.
     ///
     /// This extracts:
     /// - PKG entries to `{output}/sce_sys/`
     /// - PFS contents to `{output}/`
+    /// - `{output}/metadata.json`, if enabled with
+    ///   [`with_metadata_sidecar()`](Self::with_metadata_sidecar)
     ///
     /// PFS file extraction is parallelised with rayon.
     pub fn extract(&self, output: impl AsRef<Path>) -> Result<(), ExtractError> {
@@ -46,9 +82,72 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
         // Extract PFS contents.
         self.extract_pfs(output)?;
 
+        if self.metadata_sidecar {
+            self.write_metadata(output)?;
+        }
+
         Ok(())
     }
 
+    /// Extracts the entire PKG like [`extract()`](Self::extract) and returns
+    /// the SHA-256 of every regular PFS file.
+    ///
+    /// Each digest is computed from the decoded bytes as they are written, so
+    /// the data is not read a second time. Paths are relative to `output`, in
+    /// directory walk order; symlinks and PKG entries are not included. Files
+    /// kept by [`skip_existing_complete()`](Self::skip_existing_complete) are
+    /// read from the image to hash them.
+    ///
+    /// The result is meant for a manifest to verify the extracted files
+    /// against later.
+    pub fn extract_with_hashes(
+        &self,
+        output: impl AsRef<Path>,
+    ) -> Result<Vec<(PathBuf, [u8; 32])>, ExtractError> {
+        let output = output.as_ref();
+
+        self.extract_entries(output.join("sce_sys"))?;
+
+        let hashes = self.extract_pfs_items(output, true)?;
+
+        if self.metadata_sidecar {
+            self.write_metadata(output)?;
+        }
+
+        Ok(hashes)
+    }
+
+    /// Writes the metadata sidecar to `output`.
+    fn write_metadata(&self, output: &Path) -> Result<(), ExtractError> {
+        let inner_pfs = crate::open_inner_pfs(self.pkg)
+            .map_err(|e| ExtractError::OpenInnerPfsFailed { source: e })?;
+        let manifest = inner_pfs
+            .manifest()
+            .map_err(|e| ExtractError::ReadPfsManifestFailed { source: e })?;
+        let json = metadata::render(self.pkg, Some(&manifest))?;
+
+        let path = output.join(metadata::FILE_NAME);
+        let mut opts = OpenOptions::new();
+
+        opts.write(true);
+
+        if self.overwrite {
+            opts.create(true).truncate(true);
+        } else {
+            opts.create_new(true);
+        }
+
+        let mut file = opts
+            .open(&path)
+            .map_err(|e| ExtractError::CreateFileFailed {
+                path: path.clone(),
+                source: e,
+            })?;
+
+        file.write_all(json.as_bytes())
+            .map_err(|e| ExtractError::WriteFailed { path, source: e })
+    }
+
     /// Extracts only the PKG entries (metadata files) to the specified directory.
     pub fn extract_entries(&self, output: impl AsRef<Path>) -> Result<(), ExtractError> {
         let output = output.as_ref();
@@ -60,8 +159,8 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
             let (num, entry) =
                 result.map_err(|e| ExtractError::ReadEntryFailed { num: 0, source: e })?;
 
-            // Get file path for this entry (skip entries without known paths).
-            let path = match entry.to_path(output) {
+            // Get file path for this entry (skip entries without known paths or names).
+            let path = match self.pkg.entry_path(&entry, output) {
                 Some(p) => p,
                 None => continue,
             };
@@ -69,17 +168,23 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
             // Report progress.
             self.progress.entry_start(&path, num, total);
 
-            // Get decrypted entry data, skipping entries that can't be decrypted.
-            let data = match self.pkg.entry_data(&entry) {
-                Ok(data) => data,
-                Err(orbis_pkg::EntryDataError::NoDecryptionKey { key_index }) => {
-                    self.progress
-                        .entry_skipped(&path, &format!("no key for index {}", key_index));
-                    skipped += 1;
-                    continue;
-                }
-                Err(e) => return Err(ExtractError::GetEntryDataFailed { num, source: e }),
-            };
+            // Skip entries that can't be decrypted.
+            if let Some(reason) = self.pkg.skip_reason(&entry) {
+                self.progress.entry_skipped(&path, &reason.to_string());
+                skipped += 1;
+                continue;
+            }
+
+            // Open a reader that decrypts entry data as it is written. Unencrypted
+            // entries are written straight from the PKG instead.
+            let mut reader = self
+                .pkg
+                .entry_reader(&entry)
+                .map_err(|e| ExtractError::GetEntryDataFailed { num, source: e })?;
+            let data = (!entry.is_encrypted())
+                .then(|| self.pkg.entry_slice(&entry))
+                .transpose()
+                .map_err(|e| ExtractError::GetEntryDataFailed { num, source: e })?;
 
             // Create parent directory.
             if let Some(parent) = path.parent() {
@@ -95,11 +200,15 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
                 source: e,
             })?;
 
-            file.write_all(&data)
-                .map_err(|e| ExtractError::WriteFailed {
-                    path: path.clone(),
-                    source: e,
-                })?;
+            let result = match data {
+                Some(data) => file.write_all(data),
+                None => std::io::copy(&mut reader, &mut file).map(|_| ()),
+            };
+
+            result.map_err(|e| ExtractError::WriteFailed {
+                path: path.clone(),
+                source: e,
+            })?;
 
             extracted += 1;
         }
@@ -111,52 +220,130 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
         Ok(())
     }
 
-    /// Extracts the PFS contents to the specified directory.
+    /// Estimates how many bytes [`extract()`](Self::extract) will write.
     ///
-    /// Directories are created sequentially, then all files are extracted
-    /// in parallel using rayon.
-    pub fn extract_pfs(&self, output: impl AsRef<Path>) -> Result<(), ExtractError> {
-        let output = output.as_ref();
+    /// This is the logical size: the data size of every entry that has a known
+    /// path plus the length of every file in the inner PFS. Space taken by
+    /// directories and filesystem block rounding is not included. Entries that
+    /// end up skipped for lack of a key are still counted, so the result is an
+    /// upper bound for the data written.
+    ///
+    /// Walking the inner PFS reads its directory blocks, but no file data.
+    pub fn estimated_output_size(&self) -> Result<u64, ExtractError> {
+        let mut total = 0u64;
 
-        // Get PFS image and encryption key.
-        let pfs_image = self.pkg.get_pfs_image().ok_or(ExtractError::NoPfsImage)?;
+        for result in self.pkg.entries() {
+            let (_, entry) =
+                result.map_err(|e| ExtractError::ReadEntryFailed { num: 0, source: e })?;
 
-        // Open outer PFS (encrypted, slice-backed).
-        let outer_pfs = orbis_pfs::open_slice(pfs_image.data, Some(pfs_image.ekpfs))
-            .map_err(|e| ExtractError::OpenOuterPfsFailed { source: e })?;
+            if self.pkg.entry_path(&entry, "").is_some() {
+                total += entry.data_size() as u64;
+            }
+        }
 
-        let mut outer_root = outer_pfs
-            .root()
-            .open()
-            .map_err(|e| ExtractError::OpenOuterSuperRootFailed { source: e })?;
-
-        // Open outer uroot directory.
-        let mut outer_uroot = match outer_root.remove(b"uroot") {
-            Some(DirEntry::Directory(d)) => d
-                .open()
-                .map_err(|e| ExtractError::OpenOuterUrootFailed { source: e })?,
-            _ => return Err(ExtractError::NoOuterUroot),
-        };
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
 
-        // Get inner PFS image (pfs_image.dat).
-        let inner_file = match outer_uroot.remove(b"pfs_image.dat") {
-            Some(DirEntry::File(f)) => f,
-            _ => return Err(ExtractError::NoInnerImage),
-        };
+        collect_pfs_items(
+            self.open_inner_uroot()?,
+            Path::new(""),
+            "/",
+            &mut dirs,
+            &mut files,
+        )?;
 
-        // Convert the file handle to an Image adapter, then open the inner PFS.
-        let is_compressed = inner_file.is_compressed();
-        let file_image = inner_file.into_image();
+        total += files
+            .iter()
+            .filter(|work| !work.file.is_symlink())
+            .map(|work| work.file.len())
+            .sum::<u64>();
 
-        let inner_pfs = if is_compressed {
-            let pfsc = orbis_pfs::pfsc::PfscImage::open(file_image)
-                .map_err(|e| ExtractError::CreateDecompressorFailed { source: e })?;
-            orbis_pfs::open_image(pfsc)
-                .map_err(|e| ExtractError::OpenInnerPfsFailed { source: e })?
-        } else {
-            orbis_pfs::open_image(file_image)
-                .map_err(|e| ExtractError::OpenInnerPfsFailed { source: e })?
-        };
+        Ok(total)
+    }
+
+    /// Lists everything [`extract()`](Self::extract) would create, without
+    /// writing anything.
+    ///
+    /// The plan holds every directory and file, with sizes, relative to the
+    /// output directory. PKG entries that extraction skips carry the same
+    /// reason it reports. Whether an existing file would be kept by
+    /// [`skip_existing_complete()`](Self::skip_existing_complete) depends on
+    /// the output directory and is only decided during extraction.
+    ///
+    /// Walking the inner PFS reads its directory blocks, but no file data.
+    /// Use [`ExtractPlan::to_json()`] to hand the plan to other tools.
+    pub fn plan(&self) -> Result<ExtractPlan, ExtractError> {
+        let sce_sys = Path::new("sce_sys");
+        let mut directories = vec![sce_sys.to_path_buf()];
+        let mut files = Vec::new();
+
+        for result in self.pkg.entries() {
+            let (_, entry) =
+                result.map_err(|e| ExtractError::ReadEntryFailed { num: 0, source: e })?;
+
+            let Some(path) = self.pkg.entry_path(&entry, sce_sys) else {
+                continue;
+            };
+
+            if let Some(parent) = path.parent()
+                && !directories.iter().any(|d| d == parent)
+            {
+                directories.push(parent.to_path_buf());
+            }
+
+            files.push(PlannedFile {
+                path,
+                kind: PlannedFileKind::Entry,
+                size: entry.data_size() as u64,
+                skip_reason: self.pkg.skip_reason(&entry).map(|r| r.to_string()),
+            });
+        }
+
+        let mut dirs = Vec::new();
+        let mut pfs_files = Vec::new();
+
+        collect_pfs_items(
+            self.open_inner_uroot()?,
+            Path::new(""),
+            "/",
+            &mut dirs,
+            &mut pfs_files,
+        )?;
+
+        directories.extend(dirs);
+        files.extend(pfs_files.into_iter().map(|work| PlannedFile {
+            kind: if work.file.is_symlink() {
+                PlannedFileKind::Symlink
+            } else {
+                PlannedFileKind::File
+            },
+            size: work.file.len(),
+            path: work.output_path,
+            skip_reason: None,
+        }));
+
+        if self.metadata_sidecar {
+            let inner_pfs = crate::open_inner_pfs(self.pkg)
+                .map_err(|e| ExtractError::OpenInnerPfsFailed { source: e })?;
+            let manifest = inner_pfs
+                .manifest()
+                .map_err(|e| ExtractError::ReadPfsManifestFailed { source: e })?;
+
+            files.push(PlannedFile {
+                path: PathBuf::from(metadata::FILE_NAME),
+                kind: PlannedFileKind::Metadata,
+                size: metadata::render(self.pkg, Some(&manifest))?.len() as u64,
+                skip_reason: None,
+            });
+        }
+
+        Ok(ExtractPlan { directories, files })
+    }
+
+    /// Opens the inner PFS and returns its `uroot` directory.
+    fn open_inner_uroot(&self) -> Result<orbis_pfs::directory::Directory<'a>, ExtractError> {
+        let inner_pfs = crate::open_inner_pfs(self.pkg)
+            .map_err(|e| ExtractError::OpenInnerPfsFailed { source: e })?;
 
         let mut inner_root = inner_pfs
             .root()
@@ -164,10 +351,30 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
             .map_err(|e| ExtractError::OpenInnerSuperRootFailed { source: e })?;
 
         // Get inner uroot.
-        let inner_uroot = match inner_root.remove(b"uroot") {
-            Some(DirEntry::Directory(d)) => d,
-            _ => return Err(ExtractError::NoInnerUroot),
-        };
+        match inner_root.remove(b"uroot") {
+            Some(DirEntry::Directory(d)) => Ok(d),
+            _ => Err(ExtractError::NoInnerUroot),
+        }
+    }
+
+    /// Extracts the PFS contents to the specified directory.
+    ///
+    /// Directories are created sequentially, then all files are extracted
+    /// in parallel using rayon.
+    ///
+    /// Each created directory is checked to resolve inside `output`, so a
+    /// symlink already present in the output tree cannot redirect writes.
+    pub fn extract_pfs(&self, output: impl AsRef<Path>) -> Result<(), ExtractError> {
+        self.extract_pfs_items(output.as_ref(), false).map(|_| ())
+    }
+
+    /// Extracts the PFS contents, hashing each file if `hash` is `true`.
+    fn extract_pfs_items(
+        &self,
+        output: &Path,
+        hash: bool,
+    ) -> Result<Vec<(PathBuf, [u8; 32])>, ExtractError> {
+        let inner_uroot = self.open_inner_uroot()?;
 
         // Phase 1: Walk the directory tree and collect all work items.
         let mut dirs: Vec<PathBuf> = Vec::new();
@@ -175,35 +382,238 @@ impl<'a, R: AsRef<[u8]> + Sync, P: ExtractProgress> PkgExtractor<'a, R, P> {
 
         collect_pfs_items(inner_uroot, output, "/", &mut dirs, &mut files)?;
 
+        self.write_pfs_items(output, dirs, files, hash)
+    }
+
+    /// Extracts the PKG entries and only the PFS files that belong to the
+    /// given PlayGo chunks.
+    ///
+    /// Chunk membership comes from `playgo-chunk.dat`: a file is extracted if
+    /// any of its data lies in one of the requested chunks. Empty files and
+    /// all directories are always created, so the tree keeps its shape. PKG
+    /// entries and the metadata sidecar are written as with
+    /// [`extract()`](Self::extract).
+    ///
+    /// Use this to extract the base game (chunk `0`) without language or
+    /// optional content chunks.
+    pub fn extract_chunks(
+        &self,
+        chunks: &[u16],
+        output: impl AsRef<Path>,
+    ) -> Result<(), ExtractError> {
+        let output = output.as_ref();
+        let playgo = self
+            .pkg
+            .playgo_chunks()
+            .map_err(|e| ExtractError::ReadPlayGoChunksFailed { source: e })?;
+
+        let chunks = chunks
+            .iter()
+            .map(|&id| {
+                playgo
+                    .get(id)
+                    .ok_or(ExtractError::UnknownPlayGoChunk { id })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.extract_entries(output.join("sce_sys"))?;
+
+        let inner_uroot = self.open_inner_uroot()?;
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        let mut files: Vec<FileWork<'_>> = Vec::new();
+
+        collect_pfs_items(inner_uroot, output, "/", &mut dirs, &mut files)?;
+
+        let mut selected = Vec::with_capacity(files.len());
+
+        for work in files {
+            let extents = work
+                .file
+                .extents()
+                .map_err(|e| ExtractError::ReadPfsFileFailed {
+                    path: work.pfs_path.clone(),
+                    source: e,
+                })?;
+
+            if extents.is_empty() || extents.iter().any(|r| chunks.iter().any(|c| c.overlaps(r))) {
+                selected.push(work);
+            }
+        }
+
+        self.write_pfs_items(output, dirs, selected, false)?;
+
+        if self.metadata_sidecar {
+            self.write_metadata(output)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates `dirs` and extracts `files` collected from the inner PFS.
+    ///
+    /// Returns the digest of every regular file, relative to `output`, if
+    /// `hash` is `true`.
+    fn write_pfs_items(
+        &self,
+        output: &Path,
+        dirs: Vec<PathBuf>,
+        files: Vec<FileWork<'_>>,
+        hash: bool,
+    ) -> Result<Vec<(PathBuf, [u8; 32])>, ExtractError> {
         if dirs.is_empty() && files.is_empty() {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
+        let total_bytes = files.iter().map(|w| w.file.len()).sum();
+
         self.progress.pfs_start(files.len());
+        self.progress.pfs_start_bytes(files.len(), total_bytes);
+
+        // Resolve the output root so created paths can be checked against it.
+        create_dir_all(output).map_err(|e| ExtractError::CreateDirectoryFailed {
+            path: output.to_path_buf(),
+            source: e,
+        })?;
+
+        let root = output
+            .canonicalize()
+            .map_err(|e| ExtractError::ResolvePathFailed {
+                path: output.to_path_buf(),
+                source: e,
+            })?;
 
         // Phase 2: Create all directories (sequential — fast, must precede file writes).
+        //
+        // Directories are listed parents first, so each parent has been checked
+        // by the time a directory is created in it. A pre-existing symlink in
+        // the output tree could otherwise redirect the new directories.
         for dir in &dirs {
             self.progress.pfs_directory(dir);
-            create_dir_all(dir).map_err(|e| ExtractError::CreateDirectoryFailed {
-                path: dir.clone(),
-                source: e,
-            })?;
+
+            if std::fs::symlink_metadata(dir).is_err() {
+                create_dir(dir).map_err(|e| ExtractError::CreateDirectoryFailed {
+                    path: dir.clone(),
+                    source: e,
+                })?;
+            }
+
+            ensure_within_root(&root, dir)?;
         }
 
+        // Symlinks are recreated after all regular files exist.
+        let (links, files): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|work| work.file.is_symlink());
+
         // Phase 3: Extract all files in parallel.
         let overwrite = self.overwrite;
+        let skip_existing = self.skip_existing_complete;
+
+        let hashes = files
+            .par_iter()
+            .map(|work| {
+                self.progress.pfs_file(&work.output_path, work.file.len());
 
-        files.par_iter().try_for_each(|work| {
+                let digest =
+                    extract_single_file(work, &self.progress, overwrite, skip_existing, hash)?;
+                let path = work
+                    .output_path
+                    .strip_prefix(output)
+                    .unwrap_or(&work.output_path);
+
+                Ok(digest.map(|d| (path.to_path_buf(), d)))
+            })
+            .collect::<Result<Vec<_>, ExtractError>>()?;
+
+        // Phase 4: Recreate symlinks (sequential — typically only a handful).
+        for work in &links {
             self.progress.pfs_file(&work.output_path, work.file.len());
-            extract_single_file(work, &self.progress, overwrite)
-        })?;
+            extract_symlink(work, &root, &self.progress, overwrite, skip_existing)?;
+        }
+
+        // A link can pass the check on its own target yet escape through
+        // another link, so check where each one resolves once all exist and
+        // remove every link that leaves the root.
+        let mut escaped = None;
+
+        for work in &links {
+            if let Err(e) = ensure_within_root(&root, &work.output_path) {
+                let _ = std::fs::remove_file(&work.output_path);
+
+                escaped.get_or_insert(e);
+            }
+        }
+
+        if let Some(e) = escaped {
+            return Err(e);
+        }
 
         self.progress.pfs_completed();
 
-        Ok(())
+        Ok(hashes.into_iter().flatten().collect())
+    }
+
+    /// Reads every file of the inner PFS into memory instead of writing it to
+    /// disk.
+    ///
+    /// Returns each file's path relative to `uroot` with its decoded contents,
+    /// in directory walk order. Files are read in parallel using rayon.
+    /// Symlinks and directories are not included. Everything is held in memory
+    /// at once, so this is meant for small packages or inspection services.
+    pub fn collect(&self) -> Result<Vec<(PathBuf, Vec<u8>)>, ExtractError> {
+        let inner_uroot = self.open_inner_uroot()?;
+
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+
+        collect_pfs_items(inner_uroot, Path::new(""), "/", &mut dirs, &mut files)?;
+
+        files.retain(|work| !work.file.is_symlink());
+
+        let total_bytes = files.iter().map(|w| w.file.len()).sum();
+
+        self.progress.pfs_start(files.len());
+        self.progress.pfs_start_bytes(files.len(), total_bytes);
+
+        let contents = files
+            .par_iter()
+            .map(|work| {
+                self.progress.pfs_file(&work.output_path, work.file.len());
+
+                let read_failed = |e| ExtractError::ReadPfsFileFailed {
+                    path: work.pfs_path.clone(),
+                    source: e,
+                };
+
+                // The size comes from the image and is untrusted, so only part of
+                // it is reserved up front; the buffer grows as data is read.
+                let mut data = Vec::new();
+
+                data.try_reserve(work.file.len().min(COLLECT_RESERVE_LIMIT) as usize)
+                    .map_err(|e| {
+                        read_failed(std::io::Error::new(std::io::ErrorKind::OutOfMemory, e))
+                    })?;
+
+                work.file
+                    .reader()
+                    .read_to_end(&mut data)
+                    .map_err(read_failed)?;
+
+                self.progress.pfs_file_completed(data.len() as u64);
+
+                Ok((work.output_path.clone(), data))
+            })
+            .collect::<Result<Vec<_>, ExtractError>>()?;
+
+        self.progress.pfs_completed();
+
+        Ok(contents)
     }
 }
 
+/// Largest buffer [`PkgExtractor::collect()`] reserves for a file before
+/// reading it.
+const COLLECT_RESERVE_LIMIT: u64 = 64 * 1024 * 1024;
+
 /// A file to be extracted, collected during the directory walk.
 struct FileWork<'a> {
     file: orbis_pfs::file::File<'a>,
@@ -233,6 +643,13 @@ fn collect_pfs_items<'a>(
                 path: format!("{}{}", pfs_path, String::from_utf8_lossy(&name)),
             })?;
 
+        // Names come from the image and are untrusted; only accept a single plain component.
+        if !is_safe_name(name_str) {
+            return Err(ExtractError::UnsafeName {
+                path: format!("{}{}", pfs_path, name_str),
+            });
+        }
+
         let item_output = output.join(name_str);
         let item_pfs_path = format!("{}{}/", pfs_path, name_str);
 
@@ -241,7 +658,7 @@ fn collect_pfs_items<'a>(
                 dirs.push(item_output.clone());
                 collect_pfs_items(subdir, &item_output, &item_pfs_path, dirs, files)?;
             }
-            DirEntry::File(file) => {
+            DirEntry::File(file) | DirEntry::Symlink(file) => {
                 files.push(FileWork {
                     file,
                     output_path: item_output,
@@ -255,18 +672,245 @@ fn collect_pfs_items<'a>(
     Ok(())
 }
 
+/// Returns `true` if `name` is a single, plain path component.
+///
+/// Rejects empty names, separators of either platform, `.`, `..`, and anything
+/// that would be interpreted as a root or drive prefix. Colons are rejected on
+/// every platform, since Windows reads `C:x` as drive-relative and `a:b` as an
+/// alternate data stream.
+fn is_safe_name(name: &str) -> bool {
+    if name.is_empty() || name.contains(['/', '\\', ':']) {
+        return false;
+    }
+
+    let mut components = Path::new(name).components();
+
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// Checks that `path` resolves to a location inside `root`.
+///
+/// `root` must already be canonical. The check follows symlinks, including
+/// dangling ones, so it must be done after `path` has been created.
+fn ensure_within_root(root: &Path, path: &Path) -> Result<(), ExtractError> {
+    let resolved =
+        resolve_path(path, MAX_LINK_DEPTH).map_err(|e| ExtractError::ResolvePathFailed {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+    if !resolved.starts_with(root) {
+        return Err(ExtractError::PathOutsideOutput {
+            path: path.to_path_buf(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Maximum number of dangling symlinks [`resolve_path()`] follows.
+const MAX_LINK_DEPTH: usize = 40;
+
+/// Resolves `path` like [`Path::canonicalize()`], but also where it leads if it
+/// does not exist.
+///
+/// A dangling symlink is followed to its target, and components past the
+/// deepest existing ancestor are applied lexically. This tells where a link
+/// would point once its target is created.
+fn resolve_path(path: &Path, depth: usize) -> std::io::Result<PathBuf> {
+    match path.canonicalize() {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        v => return v,
+    }
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+
+    if let Ok(target) = std::fs::read_link(path) {
+        if depth == 0 {
+            return Err(std::io::Error::other("too many levels of symbolic links"));
+        }
+
+        return resolve_path(&parent.join(target), depth - 1);
+    }
+
+    match path.components().next_back() {
+        Some(Component::Normal(name)) => Ok(resolve_path(parent, depth)?.join(name)),
+        Some(Component::ParentDir) => {
+            let mut resolved = resolve_path(parent, depth)?;
+            resolved.pop();
+            Ok(resolved)
+        }
+        _ => Err(std::io::ErrorKind::NotFound.into()),
+    }
+}
+
+/// Returns `true` if `target`, taken relative to the directory of the link at
+/// `link`, stays inside the root `link` is relative to.
+///
+/// The check is lexical: absolute targets and `..` components climbing above
+/// the root are rejected, and other links along the way are not followed.
+/// Links that escape through another link are caught by
+/// [`ensure_within_root()`] once all of them exist.
+fn is_contained_link_target(link: &Path, target: &Path) -> bool {
+    let mut depth = link.components().count().saturating_sub(1);
+
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(v) => depth = v,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+
+    true
+}
+
+/// Recreates a PFS symlink on disk.
+///
+/// Targets that would point outside `root` are rejected. On Unix the link is
+/// created as-is, or kept if `skip_existing` and it already exists with the
+/// same target. Elsewhere the target is resolved relative to the link and
+/// copied, provided it stays inside `root`.
+fn extract_symlink<P: ExtractProgress>(
+    work: &FileWork<'_>,
+    #[cfg_attr(unix, allow(unused_variables))] root: &Path,
+    progress: &P,
+    overwrite: bool,
+    #[cfg_attr(not(unix), allow(unused_variables))] skip_existing: bool,
+) -> Result<(), ExtractError> {
+    let target = work
+        .file
+        .read_link()
+        .map_err(|e| ExtractError::ReadPfsFileFailed {
+            path: work.pfs_path.clone(),
+            source: e,
+        })?;
+
+    // Targets come from the image and are untrusted.
+    let link = Path::new(work.pfs_path.trim_matches('/'));
+
+    if !is_contained_link_target(link, Path::new(&*String::from_utf8_lossy(&target))) {
+        return Err(ExtractError::PathOutsideOutput {
+            path: work.output_path.clone(),
+        });
+    }
+
+    // A link from a previous run is complete if it has the same target.
+    #[cfg(unix)]
+    if skip_existing
+        && std::fs::read_link(&work.output_path).is_ok_and(|existing| {
+            use std::os::unix::ffi::OsStrExt;
+
+            existing.as_os_str().as_bytes() == target.as_slice()
+        })
+    {
+        progress.pfs_file_skipped(&work.output_path, work.file.len());
+        return Ok(());
+    }
+
+    if overwrite && std::fs::symlink_metadata(&work.output_path).is_ok() {
+        std::fs::remove_file(&work.output_path).map_err(|e| ExtractError::CreateFileFailed {
+            path: work.output_path.clone(),
+            source: e,
+        })?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let target = std::ffi::OsStr::from_bytes(&target);
+
+        std::os::unix::fs::symlink(target, &work.output_path).map_err(|e| {
+            ExtractError::CreateFileFailed {
+                path: work.output_path.clone(),
+                source: e,
+            }
+        })?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        let target =
+            std::str::from_utf8(&target).map_err(|_| ExtractError::UnsupportedFileName {
+                path: work.pfs_path.clone(),
+            })?;
+
+        let parent = work.output_path.parent().unwrap_or(root);
+        let source = parent.join(target);
+
+        ensure_within_root(root, &source)?;
+
+        std::fs::copy(&source, &work.output_path).map_err(|e| ExtractError::CreateFileFailed {
+            path: work.output_path.clone(),
+            source: e,
+        })?;
+    }
+
+    progress.pfs_file_completed(target.len() as u64);
+
+    Ok(())
+}
+
+/// Number of bytes between [`ExtractProgress::pfs_file_progress()`] calls.
+const PROGRESS_INTERVAL: u64 = 64 * 1024 * 1024;
+
 /// Extracts a single file from the PFS to disk.
 ///
-/// Called from rayon worker threads in parallel.
+/// Returns the SHA-256 of the file if `hash` is `true`. Called from rayon
+/// worker threads in parallel.
 fn extract_single_file<P: ExtractProgress>(
     work: &FileWork<'_>,
     progress: &P,
     overwrite: bool,
-) -> Result<(), ExtractError> {
+    skip_existing: bool,
+    hash: bool,
+) -> Result<Option<[u8; 32]>, ExtractError> {
     let mut opts = OpenOptions::new();
     opts.write(true);
 
-    if overwrite {
+    // A complete copy from a previous run can be kept as is.
+    let existing = std::fs::symlink_metadata(&work.output_path).ok();
+
+    if skip_existing
+        && existing
+            .as_ref()
+            .is_some_and(|m| m.is_file() && m.len() == work.file.len())
+    {
+        progress.pfs_file_skipped(&work.output_path, work.file.len());
+
+        // The kept copy was not written by this run; hash it from the image.
+        if !hash {
+            return Ok(None);
+        }
+
+        let mut hasher = Sha256::new();
+
+        std::io::copy(&mut work.file.reader(), &mut hasher).map_err(|e| {
+            ExtractError::ReadPfsFileFailed {
+                path: work.pfs_path.clone(),
+                source: e,
+            }
+        })?;
+
+        return Ok(Some(hasher.finalize().into()));
+    }
+
+    if overwrite || (skip_existing && existing.is_some()) {
+        // Opening with truncate follows symlinks; refuse to write through one.
+        if existing.is_some_and(|m| m.file_type().is_symlink()) {
+            return Err(ExtractError::PathOutsideOutput {
+                path: work.output_path.clone(),
+            });
+        }
+
         opts.create(true).truncate(true);
     } else {
         opts.create_new(true);
@@ -281,6 +925,8 @@ fn extract_single_file<P: ExtractProgress>(
 
     let mut buffer = vec![0u8; 8 * 1024 * 1024]; // 8MB buffer
     let mut offset = 0u64;
+    let mut reported = 0u64;
+    let mut hasher = hash.then(Sha256::new);
 
     loop {
         let read = match work.file.read_at(offset, &mut buffer) {
@@ -301,10 +947,84 @@ fn extract_single_file<P: ExtractProgress>(
                 source: e,
             })?;
 
+        if let Some(hasher) = &mut hasher {
+            hasher.update(&buffer[..read]);
+        }
+
         offset += read as u64;
+
+        if offset - reported >= PROGRESS_INTERVAL {
+            progress.pfs_file_progress(&work.output_path, offset, work.file.len());
+            reported = offset;
+        }
     }
 
     progress.pfs_file_completed(offset);
 
-    Ok(())
+    Ok(hasher.map(|h| h.finalize().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_contained_link_target, is_safe_name};
+    use std::path::Path;
+
+    #[test]
+    fn unsafe_names_are_rejected() {
+        assert!(is_safe_name("eboot.bin"));
+        assert!(is_safe_name("..."));
+
+        for name in ["", ".", "..", "a/b", "a\\b", "/abs", "C:x"] {
+            assert!(!is_safe_name(name), "{name:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn link_targets_escaping_the_root_are_rejected() {
+        let link = Path::new("data/sub/link");
+
+        assert!(is_contained_link_target(link, Path::new("file")));
+        assert!(is_contained_link_target(
+            link,
+            Path::new("./../../other/file")
+        ));
+        assert!(!is_contained_link_target(link, Path::new("../../../x")));
+        assert!(!is_contained_link_target(
+            link,
+            Path::new("a/../../../../x")
+        ));
+        assert!(!is_contained_link_target(link, Path::new("/etc/passwd")));
+        assert!(!is_contained_link_target(
+            Path::new("link"),
+            Path::new("..")
+        ));
+
+        // Each link stays inside on its own, but together they escape.
+        let chain = [("sub/l1", ".."), ("sub/l2", "l1/../x")];
+
+        for (link, target) in chain {
+            assert!(is_contained_link_target(Path::new(link), Path::new(target)));
+        }
+
+        #[cfg(unix)]
+        {
+            use super::ensure_within_root;
+
+            let dir = std::env::temp_dir().join(format!("orbis-link-chain-{}", std::process::id()));
+            let out = dir.join("out");
+
+            std::fs::create_dir_all(out.join("sub")).unwrap();
+
+            for (link, target) in chain {
+                std::os::unix::fs::symlink(target, out.join(link)).unwrap();
+            }
+
+            let root = out.canonicalize().unwrap();
+
+            assert!(ensure_within_root(&root, &out.join("sub/l1")).is_ok());
+            assert!(ensure_within_root(&root, &out.join("sub/l2")).is_err());
+
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
 }
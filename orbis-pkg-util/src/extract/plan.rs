@@ -0,0 +1,151 @@
+//! The preview of an extraction returned by
+//! [`PkgExtractor::plan()`](super::PkgExtractor::plan).
+
+use super::metadata::string;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Everything [`PkgExtractor::extract()`](super::PkgExtractor::extract) would
+/// create, without writing anything.
+///
+/// Paths are relative to the output directory. Directories are listed in
+/// creation order, parents first; files in extraction order: PKG entries,
+/// then the PFS in directory walk order, then the metadata sidecar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExtractPlan {
+    /// Directories to create.
+    pub directories: Vec<PathBuf>,
+    /// Files to write, including the ones that will be skipped.
+    pub files: Vec<PlannedFile>,
+}
+
+/// A file in an [`ExtractPlan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PlannedFile {
+    /// Path relative to the output directory.
+    pub path: PathBuf,
+    /// Where the file comes from.
+    pub kind: PlannedFileKind,
+    /// Number of bytes to write. For a symlink, the length of its target.
+    pub size: u64,
+    /// Why the file will not be written, if it will be skipped.
+    pub skip_reason: Option<String>,
+}
+
+/// The source of a [`PlannedFile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PlannedFileKind {
+    /// A PKG entry, written to `sce_sys`.
+    Entry,
+    /// A regular file of the inner PFS.
+    File,
+    /// A symbolic link of the inner PFS.
+    Symlink,
+    /// The `metadata.json` sidecar.
+    Metadata,
+}
+
+impl PlannedFileKind {
+    /// Returns the name used for this kind in [`ExtractPlan::to_json()`].
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Entry => "entry",
+            Self::File => "file",
+            Self::Symlink => "symlink",
+            Self::Metadata => "metadata",
+        }
+    }
+}
+
+impl ExtractPlan {
+    /// Returns the number of bytes that would be written, leaving out
+    /// skipped files.
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.files
+            .iter()
+            .filter(|f| f.skip_reason.is_none())
+            .map(|f| f.size)
+            .sum()
+    }
+
+    /// Renders the plan as a JSON object with `directories`, `files` and
+    /// `total_size` fields.
+    ///
+    /// Each file is an object with `path`, `kind`, `size` and `skip_reason`
+    /// (`null` unless skipped). Paths that are not valid UTF-8 are rendered
+    /// lossily.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\n  \"directories\": [");
+
+        for (i, dir) in self.directories.iter().enumerate() {
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            write!(out, "{sep}    {}", string(&dir.to_string_lossy())).unwrap();
+        }
+
+        out.push_str(if self.directories.is_empty() {
+            "],\n"
+        } else {
+            "\n  ],\n"
+        });
+        out.push_str("  \"files\": [");
+
+        for (i, file) in self.files.iter().enumerate() {
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            let skip = file
+                .skip_reason
+                .as_deref()
+                .map(string)
+                .unwrap_or_else(|| "null".into());
+
+            write!(
+                out,
+                "{sep}    {{\"path\": {}, \"kind\": {}, \"size\": {}, \"skip_reason\": {}}}",
+                string(&file.path.to_string_lossy()),
+                string(file.kind.name()),
+                file.size,
+                skip
+            )
+            .unwrap();
+        }
+
+        out.push_str(if self.files.is_empty() {
+            "],\n"
+        } else {
+            "\n  ],\n"
+        });
+        writeln!(out, "  \"total_size\": {}\n}}", self.total_size()).unwrap();
+
+        out
+    }
+
+    /// Renders the plans of several packages as one JSON array.
+    ///
+    /// Each element is an object with the `package` path and its `plan`, as
+    /// rendered by [`to_json()`](Self::to_json).
+    #[must_use]
+    pub fn batch_to_json(plans: &[(PathBuf, ExtractPlan)]) -> String {
+        let mut out = String::from("[");
+
+        for (i, (package, plan)) in plans.iter().enumerate() {
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            let plan = plan.to_json().trim_end().replace('\n', "\n    ");
+
+            write!(
+                out,
+                "{sep}  {{\n    \"package\": {},\n    \"plan\": {plan}\n  }}",
+                string(&package.to_string_lossy())
+            )
+            .unwrap();
+        }
+
+        out.push_str(if plans.is_empty() { "]\n" } else { "\n]\n" });
+
+        out
+    }
+}
@@ -33,8 +33,10 @@ pub use self::progress::{ExtractProgress, SilentProgress};
 #[cfg(feature = "cli")]
 pub use self::progress::ConsoleProgress;
 
-use snafu::{ResultExt, Snafu};
+use orbis_pfs::directory::DirEntry;
+use snafu::{OptionExt, ResultExt, Snafu};
 use std::path::Path;
+use std::sync::Arc;
 
 /// Errors that can occur when opening a PKG file from disk.
 #[derive(Debug, Snafu)]
@@ -65,3 +67,88 @@ pub unsafe fn open_pkg(path: &Path) -> Result<orbis_pkg::Pkg<memmap2::Mmap>, Ope
     let raw = unsafe { memmap2::Mmap::map(&file).context(MmapFileSnafu)? };
     orbis_pkg::Pkg::new(raw).context(ParsePkgSnafu)
 }
+
+/// Errors that can occur when opening the inner PFS of a PKG.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum OpenInnerPfsError {
+    #[snafu(display("PKG does not contain a PFS image"))]
+    NoPfsImage,
+
+    #[snafu(display("cannot open outer PFS"))]
+    OpenOuterPfs { source: orbis_pfs::OpenSliceError },
+
+    #[snafu(display("cannot open super-root on outer PFS"))]
+    OpenOuterSuperRoot {
+        source: orbis_pfs::directory::OpenError,
+    },
+
+    #[snafu(display("outer PFS does not contain uroot directory"))]
+    NoOuterUroot,
+
+    #[snafu(display("cannot open uroot on outer PFS"))]
+    OpenOuterUroot {
+        source: orbis_pfs::directory::OpenError,
+    },
+
+    #[snafu(display("outer PFS does not contain pfs_image.dat"))]
+    NoInnerImage,
+
+    #[snafu(display("cannot create decompressor for inner PFS"))]
+    CreateDecompressor { source: orbis_pfs::pfsc::OpenError },
+
+    #[snafu(display("cannot open inner PFS"))]
+    OpenInnerPfs { source: orbis_pfs::OpenImageError },
+}
+
+/// Opens the inner PFS that holds the game filesystem of a PKG.
+///
+/// This performs the whole descent: decrypts and opens the outer PFS, locates
+/// `uroot/pfs_image.dat`, wraps it in a PFSC decompressor when needed, and
+/// opens the result as the inner PFS.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let pkg = unsafe { orbis_pkg_util::open_pkg("game.pkg".as_ref())? };
+/// let pfs = orbis_pkg_util::open_inner_pfs(&pkg)?;
+/// println!("Inner PFS has {} inodes", pfs.inode_count());
+/// # Ok(())
+/// # }
+/// ```
+pub fn open_inner_pfs<R: AsRef<[u8]> + Sync>(
+    pkg: &orbis_pkg::Pkg<R>,
+) -> Result<Arc<orbis_pfs::Pfs<'_>>, OpenInnerPfsError> {
+    // Get PFS image and encryption key.
+    let pfs_image = pkg.get_pfs_image().context(NoPfsImageSnafu)?;
+
+    // Open outer PFS (encrypted, slice-backed).
+    let outer_pfs =
+        orbis_pfs::open_slice(pfs_image.data, Some(pfs_image.ekpfs)).context(OpenOuterPfsSnafu)?;
+
+    let mut outer_root = outer_pfs.root().open().context(OpenOuterSuperRootSnafu)?;
+
+    // Open outer uroot directory.
+    let mut outer_uroot = match outer_root.remove(b"uroot") {
+        Some(DirEntry::Directory(d)) => d.open().context(OpenOuterUrootSnafu)?,
+        _ => return NoOuterUrootSnafu.fail(),
+    };
+
+    // Get inner PFS image (pfs_image.dat).
+    let inner_file = match outer_uroot.remove(b"pfs_image.dat") {
+        Some(DirEntry::File(f)) => f,
+        _ => return NoInnerImageSnafu.fail(),
+    };
+
+    // Convert the file handle to an Image adapter, then open the inner PFS.
+    let is_compressed = inner_file.is_compressed();
+    let file_image = inner_file.into_image();
+
+    if is_compressed {
+        let pfsc = orbis_pfs::pfsc::PfscImage::open(file_image).context(CreateDecompressorSnafu)?;
+        orbis_pfs::open_image(pfsc).context(OpenInnerPfsSnafu)
+    } else {
+        orbis_pfs::open_image(file_image).context(OpenInnerPfsSnafu)
+    }
+}
@@ -1,9 +1,11 @@
 mod cli;
 
 use clap::Parser;
-use cli::{Cli, Command};
-use orbis_pkg_util::{ConsoleProgress, PkgExtractor, SilentProgress};
+use cli::{Cli, Command, ExtractArgs};
+use orbis_pkg_util::extract::ExtractPlan;
+use orbis_pkg_util::{ConsoleProgress, ExtractProgress, PkgExtractor, SilentProgress};
 use snafu::{ResultExt, Snafu};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Top-level application errors for orbis-pkg-util.
@@ -23,8 +25,23 @@ enum Error {
     #[snafu(display("failed to read entry"))]
     ReadEntry { source: orbis_pkg::EntryReadError },
 
+    #[snafu(display("failed to verify '{}'", path.display()))]
+    Verify {
+        path: PathBuf,
+        source: orbis_pkg::VerifyError,
+    },
+
+    #[snafu(display("failed to extract {failed} PKG file(s)"))]
+    BatchFailed { failed: usize },
+
     #[snafu(display("failed to get current directory"))]
     GetCurrentDir { source: std::io::Error },
+
+    #[snafu(display("failed to read directory '{}'", path.display()))]
+    ReadDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
 }
 
 type Result<T, E = Error> = std::result::Result<T, E>;
@@ -34,32 +51,112 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Command::Extract {
-            pkg_path,
-            output,
-            force,
-            quiet,
-        } => cmd_extract(&pkg_path, output.as_deref(), force, quiet),
-        Command::Info { pkg_path } => cmd_info(&pkg_path),
+        Command::Extract(args) => cmd_extract(&args),
+        Command::Info { pkg_path, title_id } => cmd_info(&pkg_path, title_id.as_deref()),
         Command::List { pkg_path } => cmd_list(&pkg_path),
+        Command::Verify { pkg_path } => cmd_verify(&pkg_path),
+        Command::Diff { old_path, new_path } => {
+            // Like diff(1), exit with 1 if the packages differ.
+            if cmd_diff(&old_path, &new_path)? {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn cmd_extract(args: &ExtractArgs) -> Result<()> {
+    let batch = args.pkg_path.is_dir();
+    let mut failed = 0usize;
+    let mut plans = Vec::new();
+
+    for path in pkg_paths(&args.pkg_path)? {
+        match extract_path(args, &path, batch, &mut plans) {
+            // One bad package should not stop the rest of a batch.
+            Err(e) if batch => {
+                eprintln!(
+                    "Failed to extract {}: {}",
+                    path.display(),
+                    snafu::Report::from_error(e)
+                );
+                failed += 1;
+            }
+            result => result?,
+        }
     }
+
+    // A batch prints one JSON array, so the output stays a single document.
+    if args.dry_run {
+        if batch {
+            print!("{}", ExtractPlan::batch_to_json(&plans));
+        } else if let Some((_, plan)) = plans.first() {
+            print!("{}", plan.to_json());
+        }
+    }
+
+    snafu::ensure!(failed == 0, BatchFailedSnafu { failed });
+
+    Ok(())
 }
 
-fn cmd_extract(path: &Path, output: Option<&Path>, force: bool, quiet: bool) -> Result<()> {
+/// Extracts the PKG at `path`, if it matches the title ID filter, or adds its
+/// plan to `plans` for a dry run.
+///
+/// In batch mode each package goes to a directory named after its file, since
+/// the base game, patches and DLC of a title share the same title ID.
+fn extract_path(
+    args: &ExtractArgs,
+    path: &Path,
+    batch: bool,
+    plans: &mut Vec<(PathBuf, ExtractPlan)>,
+) -> Result<()> {
     let pkg = unsafe { orbis_pkg_util::open_pkg(path).context(OpenPkgSnafu { path })? };
 
+    // Keep stdout to the JSON plan on a dry run.
+    let quiet = args.quiet || args.dry_run;
+
+    if !matches_title(path, &pkg, args.title_id.as_deref(), quiet) {
+        return Ok(());
+    }
+
+    if args.dry_run {
+        let plan = PkgExtractor::new(&pkg, SilentProgress, args.force)
+            .with_metadata_sidecar(args.metadata)
+            .plan()
+            .context(ExtractSnafu)?;
+
+        plans.push((path.to_path_buf(), plan));
+        return Ok(());
+    }
+
     // Use title ID from content ID as default output directory name.
-    let output_dir = match output {
-        Some(path) => path.to_path_buf(),
-        None => {
-            let title_id = pkg.header().content_id().title_id();
-            std::env::current_dir()
-                .context(GetCurrentDirSnafu)?
-                .join(title_id)
-        }
+    let content_id = pkg.header().content_id();
+    let name = if batch {
+        PathBuf::from(path.file_stem().unwrap_or(path.as_os_str()))
+    } else if args.full_name {
+        PathBuf::from(content_id.safe_dir_name())
+    } else {
+        PathBuf::from(content_id.title_id())
+    };
+    let output_dir = match &args.output {
+        Some(output) if batch => output.join(name),
+        Some(output) => output.clone(),
+        None => std::env::current_dir()
+            .context(GetCurrentDirSnafu)?
+            .join(name),
     };
 
-    if !quiet {
+    extract_one(args, path, &pkg, &output_dir)
+}
+
+fn extract_one<R: AsRef<[u8]> + Sync>(
+    args: &ExtractArgs,
+    path: &Path,
+    pkg: &orbis_pkg::Pkg<R>,
+    output_dir: &Path,
+) -> Result<()> {
+    if !args.quiet {
         println!(
             "Extracting {} to {}...",
             path.display(),
@@ -70,27 +167,117 @@ fn cmd_extract(path: &Path, output: Option<&Path>, force: bool, quiet: bool) ->
     let start = std::time::Instant::now();
 
     // Extract based on verbosity.
-    if quiet {
-        let extractor = PkgExtractor::new(&pkg, SilentProgress, force);
-        extractor.extract(&output_dir).context(ExtractSnafu)?;
+    if args.quiet {
+        let extractor =
+            PkgExtractor::new(pkg, SilentProgress, args.force).with_metadata_sidecar(args.metadata);
+        run_extractor(&extractor, &args.chunks, output_dir)?;
     } else {
-        let extractor = PkgExtractor::new(&pkg, ConsoleProgress::new(), force);
-        extractor.extract(&output_dir).context(ExtractSnafu)?;
+        let extractor = PkgExtractor::new(pkg, ConsoleProgress::new(), args.force)
+            .with_metadata_sidecar(args.metadata);
+        run_extractor(&extractor, &args.chunks, output_dir)?;
     }
 
     let elapsed = start.elapsed();
 
-    if !quiet {
+    if !args.quiet {
         println!("Done in {:.2}s.", elapsed.as_secs_f64());
     }
 
     Ok(())
 }
 
-fn cmd_info(path: &Path) -> Result<()> {
+/// Returns `path` itself, or the `.pkg` files in it, sorted, if it is a
+/// directory.
+fn pkg_paths(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut paths = Vec::new();
+
+    for entry in std::fs::read_dir(path).context(ReadDirSnafu { path })? {
+        let entry = entry.context(ReadDirSnafu { path })?;
+        let path = entry.path();
+        let is_pkg = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pkg"));
+
+        if is_pkg && path.is_file() {
+            paths.push(path);
+        }
+    }
+
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Returns `true` if `pkg` has the title ID to filter by, if any, printing
+/// a note for skipped packages unless `quiet`.
+fn matches_title<R: AsRef<[u8]> + Sync>(
+    path: &Path,
+    pkg: &orbis_pkg::Pkg<R>,
+    title_id: Option<&str>,
+    quiet: bool,
+) -> bool {
+    let Some(title_id) = title_id else {
+        return true;
+    };
+
+    if pkg.has_title_id(title_id) {
+        return true;
+    }
+
+    if !quiet {
+        println!(
+            "Skipping {} (title ID {})",
+            path.display(),
+            pkg.header().content_id().title_id()
+        );
+    }
+
+    false
+}
+
+/// Extracts everything, or only the given PlayGo chunks if any.
+fn run_extractor<R: AsRef<[u8]> + Sync, P: ExtractProgress>(
+    extractor: &PkgExtractor<'_, R, P>,
+    chunks: &[u16],
+    output: &Path,
+) -> Result<()> {
+    if chunks.is_empty() {
+        extractor.extract(output).context(ExtractSnafu)
+    } else {
+        extractor
+            .extract_chunks(chunks, output)
+            .context(ExtractSnafu)
+    }
+}
+
+fn cmd_info(path: &Path, title_id: Option<&str>) -> Result<()> {
+    let mut first = true;
+
+    for path in pkg_paths(path)? {
+        let pkg = unsafe { orbis_pkg_util::open_pkg(&path).context(OpenPkgSnafu { path: &path })? };
+
+        if !matches_title(&path, &pkg, title_id, true) {
+            continue;
+        }
+
+        if !first {
+            println!();
+        }
+
+        first = false;
+        print_info(&path, &pkg);
+    }
+
+    Ok(())
+}
+
+fn print_info<R: AsRef<[u8]> + Sync>(path: &Path, pkg: &orbis_pkg::Pkg<R>) {
     use orbis_pkg::header::{content_type_name, drm_type_name};
 
-    let pkg = unsafe { orbis_pkg_util::open_pkg(path).context(OpenPkgSnafu { path })? };
     let header = pkg.header();
     let content_id = header.content_id();
 
@@ -108,11 +295,21 @@ fn cmd_info(path: &Path) -> Result<()> {
         content_type_name(header.content_type())
     );
     println!("Content Flags:  {}", header.content_flags());
+    println!(
+        "Package Kind:   {}{}",
+        header.package_kind(),
+        if header.requires_base_game() {
+            " (requires the base game)"
+        } else {
+            ""
+        }
+    );
     println!(
         "DRM Type:       0x{:02X} ({})",
         header.drm_type(),
         drm_type_name(header.drm_type())
     );
+    println!("Platform:       {}", header.platform());
     println!("PKG Type:       0x{:08X}", header.pkg_type());
     println!("PKG Size:       {} bytes", header.pkg_size());
     println!("File Count:     {}", header.file_count());
@@ -120,8 +317,6 @@ fn cmd_info(path: &Path) -> Result<()> {
     println!("Table Offset:   0x{:X}", header.table_offset());
     println!("PFS Offset:     0x{:X}", header.pfs_offset());
     println!("PFS Size:       {} bytes", header.pfs_size());
-
-    Ok(())
 }
 
 fn cmd_list(path: &Path) -> Result<()> {
@@ -133,8 +328,8 @@ fn cmd_list(path: &Path) -> Result<()> {
 
     for result in pkg.entries() {
         let (index, entry) = result.context(ReadEntrySnafu)?;
-        let path_str = entry
-            .to_path(Path::new(""))
+        let path_str = pkg
+            .entry_path(&entry, "")
             .map(|p| p.display().to_string())
             .unwrap_or_else(|| format!("(id: 0x{:08X})", entry.id()));
 
@@ -149,3 +344,153 @@ fn cmd_list(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+fn cmd_verify(path: &Path) -> Result<()> {
+    let pkg = unsafe { orbis_pkg_util::open_pkg(path).context(OpenPkgSnafu { path })? };
+
+    println!("Verifying {}...", path.display());
+
+    pkg.verify_digests().context(VerifySnafu { path })?;
+
+    println!("All digests match.");
+
+    Ok(())
+}
+
+/// Prints the differences between two packages and returns `true` if there
+/// are any.
+fn cmd_diff(old_path: &Path, new_path: &Path) -> Result<bool> {
+    let old =
+        unsafe { orbis_pkg_util::open_pkg(old_path).context(OpenPkgSnafu { path: old_path })? };
+    let new =
+        unsafe { orbis_pkg_util::open_pkg(new_path).context(OpenPkgSnafu { path: new_path })? };
+
+    println!("--- {}", old_path.display());
+    println!("+++ {}", new_path.display());
+
+    let mut changes = 0usize;
+
+    // Compare header fields.
+    let (a, b) = (old.header(), new.header());
+    let fields = [
+        (
+            "Content ID",
+            a.content_id().to_string(),
+            b.content_id().to_string(),
+        ),
+        (
+            "Content Type",
+            format!("0x{:02X} ({})", a.content_type(), a.content_type_name()),
+            format!("0x{:02X} ({})", b.content_type(), b.content_type_name()),
+        ),
+        (
+            "Content Flags",
+            a.content_flags().to_string(),
+            b.content_flags().to_string(),
+        ),
+        (
+            "DRM Type",
+            format!("0x{:02X} ({})", a.drm_type(), a.drm_type_name()),
+            format!("0x{:02X} ({})", b.drm_type(), b.drm_type_name()),
+        ),
+        (
+            "Platform",
+            a.platform().to_string(),
+            b.platform().to_string(),
+        ),
+        (
+            "PKG Type",
+            format!("0x{:08X}", a.pkg_type()),
+            format!("0x{:08X}", b.pkg_type()),
+        ),
+        (
+            "PKG Size",
+            a.pkg_size().to_string(),
+            b.pkg_size().to_string(),
+        ),
+        (
+            "File Count",
+            a.file_count().to_string(),
+            b.file_count().to_string(),
+        ),
+        (
+            "Entry Count",
+            a.entry_count().to_string(),
+            b.entry_count().to_string(),
+        ),
+        (
+            "PFS Size",
+            a.pfs_size().to_string(),
+            b.pfs_size().to_string(),
+        ),
+    ];
+
+    for (name, a, b) in fields {
+        if a != b {
+            println!("header  {:<14} {} -> {}", name, a, b);
+            changes += 1;
+        }
+    }
+
+    // Compare entry tables by ID.
+    let old_entries = entry_sizes(&old)?;
+    let new_entries = entry_sizes(&new)?;
+
+    for (id, &size) in &old_entries {
+        match new_entries.get(id) {
+            None => {
+                println!("removed 0x{:08X}  {:>10}  {}", id, size, entry_name(*id));
+                changes += 1;
+            }
+            Some(&new_size) if new_size != size => {
+                println!(
+                    "resized 0x{:08X}  {:>10} -> {}  {}",
+                    id,
+                    size,
+                    new_size,
+                    entry_name(*id)
+                );
+                changes += 1;
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (id, &size) in &new_entries {
+        if !old_entries.contains_key(id) {
+            println!("added   0x{:08X}  {:>10}  {}", id, size, entry_name(*id));
+            changes += 1;
+        }
+    }
+
+    if changes == 0 {
+        println!("No differences.");
+    } else {
+        println!("{} difference(s).", changes);
+    }
+
+    Ok(changes > 0)
+}
+
+/// Collects the data size of every entry, keyed by entry ID.
+///
+/// If several entries share an ID, the first one is used, as with
+/// [`Pkg::find_entry()`](orbis_pkg::Pkg::find_entry).
+fn entry_sizes<R: AsRef<[u8]> + Sync>(pkg: &orbis_pkg::Pkg<R>) -> Result<BTreeMap<u32, usize>> {
+    let mut sizes = BTreeMap::new();
+
+    for result in pkg.entries() {
+        let (_, entry) = result.context(ReadEntrySnafu)?;
+        sizes.entry(entry.id()).or_insert(entry.data_size());
+    }
+
+    Ok(sizes)
+}
+
+/// Returns the display path for an entry ID, or an empty string if unknown.
+fn entry_name(id: u32) -> String {
+    orbis_pkg::entry::EntryId::from_u32(id)
+        .to_path(Path::new(""))
+        .map(|p| p.display().to_string())
+        .unwrap_or_default()
+}
@@ -1,4 +1,16 @@
+#[cfg(feature = "cli")]
+use std::collections::HashMap;
 use std::path::Path;
+#[cfg(feature = "cli")]
+use std::sync::Mutex;
+#[cfg(feature = "cli")]
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(feature = "cli")]
+use std::thread::ThreadId;
+
+/// Bytes per megabyte, for rate display.
+#[cfg(feature = "cli")]
+const MB: f64 = 1024.0 * 1024.0;
 
 /// Trait for receiving extraction progress updates.
 ///
@@ -17,15 +29,29 @@ pub trait ExtractProgress: Send + Sync {
     /// Called when starting PFS extraction.
     fn pfs_start(&self, _total_items: usize) {}
 
+    /// Called right after [`pfs_start`](Self::pfs_start) with the total logical
+    /// size of all files, for byte-based progress.
+    fn pfs_start_bytes(&self, _total_files: usize, _total_bytes: u64) {}
+
     /// Called when creating a directory from PFS.
     fn pfs_directory(&self, _path: &Path) {}
 
     /// Called when starting to extract a file from PFS.
     fn pfs_file(&self, _path: &Path, _size: u64) {}
 
+    /// Called periodically while a large PFS file is being written.
+    ///
+    /// Files are extracted in parallel, so calls for different paths may
+    /// interleave. Calls are throttled to one per 64 MiB written.
+    fn pfs_file_progress(&self, _path: &Path, _bytes_done: u64, _bytes_total: u64) {}
+
     /// Called when a PFS file has been fully extracted.
     fn pfs_file_completed(&self, _written: u64) {}
 
+    /// Called instead of [`pfs_file_completed`](Self::pfs_file_completed) when
+    /// a PFS file is skipped because a complete copy already exists.
+    fn pfs_file_skipped(&self, _path: &Path, _size: u64) {}
+
     /// Called when PFS extraction is complete.
     fn pfs_completed(&self) {}
 }
@@ -37,20 +63,70 @@ impl ExtractProgress for SilentProgress {}
 
 /// Console progress reporter using an indicatif progress bar.
 ///
-/// PKG entries are printed as before (few items, no contention concern).
-/// PFS file extraction uses a progress bar that redraws at a fixed rate,
-/// avoiding the stdout lock contention caused by per-file `println!`.
+/// PKG entries and PFS files each get a progress bar that redraws at a fixed
+/// rate, avoiding the stdout lock contention caused by per-file `println!`.
+/// Skipped entries are printed above the entry bar. Once the total size is
+/// known from [`pfs_start_bytes`](ExtractProgress::pfs_start_bytes), the PFS
+/// bar counts bytes, so its throughput and ETA hold up when file sizes vary.
 #[cfg(feature = "cli")]
 pub struct ConsoleProgress {
+    entry_bar: indicatif::ProgressBar,
+    /// Whether the entry bar has been set up for the current entry phase.
+    entry_started: AtomicBool,
     pfs_bar: indicatif::ProgressBar,
+    /// Whether the PFS bar counts bytes rather than files.
+    pfs_by_bytes: AtomicBool,
+    /// Number of PFS files to extract.
+    pfs_total: AtomicU64,
+    /// Number of PFS files completed or skipped.
+    pfs_done: AtomicU64,
+    /// Total bytes written by completed PFS files, used for the rate display.
+    pfs_bytes: AtomicU64,
+    /// Number of PFS files skipped because they were already complete.
+    pfs_skipped: AtomicU64,
+    /// Bytes of the file in progress on each worker thread that have already
+    /// been added to the byte bar.
+    pfs_partial: Mutex<HashMap<ThreadId, u64>>,
 }
 
 #[cfg(feature = "cli")]
 impl ConsoleProgress {
     pub fn new() -> Self {
         Self {
+            entry_bar: indicatif::ProgressBar::hidden(),
+            entry_started: AtomicBool::new(false),
             pfs_bar: indicatif::ProgressBar::hidden(),
+            pfs_by_bytes: AtomicBool::new(false),
+            pfs_total: AtomicU64::new(0),
+            pfs_done: AtomicU64::new(0),
+            pfs_bytes: AtomicU64::new(0),
+            pfs_skipped: AtomicU64::new(0),
+            pfs_partial: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a finished PFS file of `size` bytes, minus what was already added
+    /// to the byte bar while it was written.
+    fn pfs_file_done(&self, size: u64) {
+        let done = self.pfs_done.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if !self.pfs_by_bytes.load(Ordering::Relaxed) {
+            self.pfs_bar.inc(1);
+            return;
         }
+
+        let reported = self
+            .pfs_partial
+            .lock()
+            .unwrap()
+            .remove(&std::thread::current().id())
+            .unwrap_or(0);
+
+        self.pfs_bar.inc(size.saturating_sub(reported));
+        self.pfs_bar.set_message(format!(
+            "{done}/{} files",
+            self.pfs_total.load(Ordering::Relaxed)
+        ));
     }
 }
 
@@ -63,6 +139,44 @@ impl Default for ConsoleProgress {
 
 #[cfg(feature = "cli")]
 impl ExtractProgress for ConsoleProgress {
+    fn entry_start(&self, path: &Path, current: usize, total: usize) {
+        if !self.entry_started.swap(true, Ordering::Relaxed) {
+            self.entry_bar
+                .set_draw_target(indicatif::ProgressDrawTarget::stderr());
+            self.entry_bar.set_style(
+                indicatif::ProgressStyle::default_bar()
+                    .template("Extracting metadata: {pos}/{len} {wide_msg}")
+                    .unwrap(),
+            );
+            self.entry_bar.reset();
+        }
+
+        self.entry_bar.set_length(total as u64);
+        self.entry_bar.set_position(current as u64 + 1);
+        self.entry_bar.set_message(
+            path.file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .into_owned(),
+        );
+    }
+
+    fn entry_skipped(&self, path: &Path, reason: &str) {
+        self.entry_bar
+            .println(format!("Skipped {}: {}", path.display(), reason));
+    }
+
+    fn entries_completed(&self, extracted: usize, skipped: usize) {
+        self.entry_started.store(false, Ordering::Relaxed);
+        self.entry_bar.finish_and_clear();
+
+        if skipped > 0 {
+            println!("Extracted {extracted} entries ({skipped} skipped).");
+        } else {
+            println!("Extracted {extracted} entries.");
+        }
+    }
+
     fn pfs_start(&self, total_items: usize) {
         self.pfs_bar
             .set_draw_target(indicatif::ProgressDrawTarget::stderr());
@@ -70,20 +184,91 @@ impl ExtractProgress for ConsoleProgress {
         self.pfs_bar.set_position(0);
         self.pfs_bar.set_style(
             indicatif::ProgressStyle::default_bar()
-                .template("{bar:40.cyan/blue} {pos}/{len} files [{elapsed_precise}]")
+                .template(
+                    "{bar:40.cyan/blue} {pos}/{len} files {msg} [{elapsed_precise}, ETA {eta}]",
+                )
                 .unwrap()
                 .progress_chars("━╸─"),
         );
         self.pfs_bar.reset();
+        self.pfs_by_bytes.store(false, Ordering::Relaxed);
+        self.pfs_total.store(total_items as u64, Ordering::Relaxed);
+        self.pfs_done.store(0, Ordering::Relaxed);
+        self.pfs_bytes.store(0, Ordering::Relaxed);
+        self.pfs_skipped.store(0, Ordering::Relaxed);
+        self.pfs_partial.lock().unwrap().clear();
     }
 
-    fn pfs_file_completed(&self, _written: u64) {
-        self.pfs_bar.inc(1);
+    fn pfs_start_bytes(&self, total_files: usize, total_bytes: u64) {
+        self.pfs_bar.set_length(total_bytes);
+        self.pfs_bar.set_position(0);
+        self.pfs_bar.set_style(
+            indicatif::ProgressStyle::default_bar()
+                .template(
+                    "{bar:40.cyan/blue} {bytes}/{total_bytes} {msg} {binary_bytes_per_sec} [{elapsed_precise}, ETA {eta}]",
+                )
+                .unwrap()
+                .progress_chars("━╸─"),
+        );
+        self.pfs_bar.set_message(format!("0/{total_files} files"));
+        self.pfs_bar.reset();
+        self.pfs_by_bytes.store(true, Ordering::Relaxed);
+        self.pfs_total.store(total_files as u64, Ordering::Relaxed);
+    }
+
+    fn pfs_file_progress(&self, _path: &Path, bytes_done: u64, _bytes_total: u64) {
+        if !self.pfs_by_bytes.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // A file is written on one worker thread from start to finish.
+        let previous = self
+            .pfs_partial
+            .lock()
+            .unwrap()
+            .insert(std::thread::current().id(), bytes_done)
+            .unwrap_or(0);
+
+        self.pfs_bar.inc(bytes_done.saturating_sub(previous));
+    }
+
+    fn pfs_file_completed(&self, written: u64) {
+        let bytes = self.pfs_bytes.fetch_add(written, Ordering::Relaxed) + written;
+        let secs = self.pfs_bar.elapsed().as_secs_f64();
+
+        // The byte bar shows the rate itself.
+        if secs > 0.0 && !self.pfs_by_bytes.load(Ordering::Relaxed) {
+            self.pfs_bar
+                .set_message(format!("{:.1} MB/s", bytes as f64 / MB / secs));
+        }
+
+        self.pfs_file_done(written);
+    }
+
+    fn pfs_file_skipped(&self, _path: &Path, size: u64) {
+        self.pfs_skipped.fetch_add(1, Ordering::Relaxed);
+        self.pfs_file_done(size);
     }
 
     fn pfs_completed(&self) {
-        let total = self.pfs_bar.position();
+        let skipped = self.pfs_skipped.load(Ordering::Relaxed);
+        let total = self.pfs_done.load(Ordering::Relaxed) - skipped;
+        let bytes = self.pfs_bytes.load(Ordering::Relaxed);
         self.pfs_bar.finish_and_clear();
-        println!("PFS extraction complete ({} files).", total);
+
+        if skipped > 0 {
+            println!(
+                "PFS extraction complete ({} files, {:.1} MB, {} already complete).",
+                total,
+                bytes as f64 / MB,
+                skipped
+            );
+        } else {
+            println!(
+                "PFS extraction complete ({} files, {:.1} MB).",
+                total,
+                bytes as f64 / MB
+            );
+        }
     }
 }
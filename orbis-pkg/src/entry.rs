@@ -55,16 +55,40 @@ impl PkgEntry {
         EntryId::from_u32(self.id())
     }
 
-    /// Returns `true` if this entry is encrypted.
+    /// Returns the offset of this entry's name within the entry names table.
+    ///
+    /// The table is the data of the [`EntryId::EntryNames`] entry.
+    #[must_use]
+    pub const fn filename_offset(&self) -> u32 {
+        self.raw_entry.filename_offset.get()
+    }
+
+    /// Returns the raw first flags word.
+    ///
+    /// Only bit 31 (encrypted) is interpreted by this crate.
+    #[must_use]
+    pub const fn flags1(&self) -> u32 {
+        self.raw_entry.flags1.get()
+    }
+
+    /// Returns the raw second flags word.
+    ///
+    /// Only bits 12..=15 (key index) are interpreted by this crate.
+    #[must_use]
+    pub const fn flags2(&self) -> u32 {
+        self.raw_entry.flags2.get()
+    }
+
+    /// Returns `true` if this entry is encrypted (bit 31 of `flags1`).
     #[must_use]
     pub const fn is_encrypted(&self) -> bool {
-        self.raw_entry.flags1.get() & 0x80000000 != 0
+        self.flags1() & 0x80000000 != 0
     }
 
-    /// Returns the key index used for encryption.
+    /// Returns the key index used for encryption (bits 12..=15 of `flags2`).
     #[must_use]
     pub const fn key_index(&self) -> usize {
-        ((self.raw_entry.flags2.get() & 0xf000) >> 12) as _
+        ((self.flags2() & 0xf000) >> 12) as _
     }
 
     /// Returns the data offset within the PKG file.
@@ -79,6 +103,15 @@ impl PkgEntry {
         self.raw_entry.data_size.get() as _
     }
 
+    /// Returns the raw trailing 8-byte field of the entry.
+    ///
+    /// This field is reserved and its meaning is unknown; it is exposed
+    /// read-only so it can be compared across packages.
+    #[must_use]
+    pub const fn padding_raw(&self) -> u64 {
+        self.raw_entry.padding.get()
+    }
+
     /// Converts the entry to its raw byte representation.
     #[must_use]
     pub fn as_bytes(&self) -> &[u8] {
@@ -87,7 +120,8 @@ impl PkgEntry {
 
     /// Converts the entry ID to a filesystem path relative to the given base.
     ///
-    /// Returns `None` if the entry ID is not recognized.
+    /// Returns `None` if the entry ID has no known path. See
+    /// [`EntryId::to_path()`] for provisional names.
     #[must_use]
     pub fn to_path<B: AsRef<Path>>(&self, base: B) -> Option<PathBuf> {
         self.entry_id().to_path(base)
@@ -161,6 +195,11 @@ pub enum EntryId {
 
     // Trophy entries (0x1400 - 0x1463)
     /// `trophy00.trp` .. `trophy99.trp`
+    ///
+    /// The id range holds exactly 100 entries, so [`EntryId::from_u32()`] only
+    /// produces indices `0..=99`. A manually constructed index above 99 has no
+    /// valid id, but [`EntryId::to_path()`] still gives it a unique name
+    /// (e.g. `trophy100.trp`).
     Trophy(u8),
 
     /// Unrecognized entry ID.
@@ -236,6 +275,25 @@ impl EntryId {
     }
 
     /// Converts a raw numeric entry ID into an [`EntryId`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::entry::EntryId;
+    ///
+    /// // Every id in the trophy range round-trips and maps to a unique path.
+    /// let mut paths = std::collections::HashSet::new();
+    ///
+    /// for raw in 0x1400..=0x1463 {
+    ///     let id = EntryId::from_u32(raw);
+    ///     assert!(matches!(id, EntryId::Trophy(_)));
+    ///     assert_eq!(id.as_u32(), raw);
+    ///     assert!(paths.insert(id.to_path("").unwrap()));
+    /// }
+    ///
+    /// assert_eq!(paths.len(), 100);
+    /// assert_eq!(EntryId::from_u32(0x1464), EntryId::Unknown(0x1464));
+    /// ```
     #[must_use]
     pub const fn from_u32(raw: u32) -> Self {
         match raw {
@@ -304,7 +362,21 @@ impl EntryId {
 
     /// Converts this entry ID to a filesystem path relative to the given base.
     ///
-    /// Returns `None` if the entry ID is not recognized (or cannot be represented).
+    /// Unrecognized IDs inside the system (`0x0400 - 0x0409`) and image
+    /// (`0x1200 - 0x12DF`) ranges get provisional names such as
+    /// `system_0x0405.dat` or `image_0x1221.dat`, since they are clearly
+    /// package metadata. These names are not official and may change once the
+    /// entries are identified.
+    ///
+    /// Returns `None` for other unrecognized IDs.
+    ///
+    /// ```
+    /// use orbis_pkg::entry::EntryId;
+    ///
+    /// let path = EntryId::from_u32(0x405).to_path("sce_sys").unwrap();
+    /// assert_eq!(path, std::path::Path::new("sce_sys/system_0x0405.dat"));
+    /// assert_eq!(EntryId::from_u32(0x2000).to_path("sce_sys"), None);
+    /// ```
     #[must_use]
     pub fn to_path<B: AsRef<Path>>(self, base: B) -> Option<PathBuf> {
         let base = base.as_ref();
@@ -370,6 +442,14 @@ impl EntryId {
             // Trophy entries
             Self::Trophy(idx) => base.join("trophy").join(format!("trophy{:02}.trp", idx)),
 
+            // Provisional names for gaps in known ranges
+            Self::Unknown(id @ 0x00000400..=0x00000409) => {
+                base.join(format!("system_0x{:04x}.dat", id))
+            }
+            Self::Unknown(id @ 0x00001200..=0x000012df) => {
+                base.join(format!("image_0x{:04x}.dat", id))
+            }
+
             Self::Unknown(_) => return None,
         })
     }
@@ -22,6 +22,12 @@ type Result<T, E = ReadError> = std::result::Result<T, E>;
 
 const PKG_MAGIC: u32 = 0x7F434E54;
 
+/// The size of the PKG header on disk.
+pub const HEADER_SIZE: usize = size_of::<PkgHeaderRaw>();
+
+/// Offset of the header digest, which covers every header byte before it.
+pub const PKG_DIGEST_OFFSET: usize = 0xFE0;
+
 #[derive(Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
 #[repr(C)]
 pub struct PkgHeaderRaw {
@@ -90,6 +96,11 @@ pub struct PkgHeaderRaw {
 ///
 /// Format: `<service_id><region>-<title_id>_<version>-<label>`
 /// Example: `UP0102-CUSA03173_00-PSYCHONAUTS1PS40`
+///
+/// Equality, ordering, and hashing compare all 36 raw bytes, including
+/// whatever follows the first NUL in the title ID or label. Use
+/// [`ContentId::normalized()`] when IDs from differently padded packages
+/// should compare equal, e.g. as `HashMap` keys.
 #[derive(
     Clone,
     Copy,
@@ -126,6 +137,14 @@ pub struct ContentId {
 }
 
 impl ContentId {
+    /// Constructs a content ID directly from its 36-byte on-disk representation.
+    ///
+    /// No validation is performed; the bytes may contain NULs or invalid UTF-8.
+    #[must_use]
+    pub const fn from_bytes(bytes: &[u8; 36]) -> Self {
+        zerocopy::transmute!(*bytes)
+    }
+
     /// Returns the service ID (e.g., "UP", "EP", "JP").
     #[must_use]
     pub fn service_id(&self) -> &str {
@@ -138,7 +157,11 @@ impl ContentId {
         std::str::from_utf8(&self.publisher_code).unwrap_or("")
     }
 
-    /// Returns the title ID (e.g., "CUSA03173").
+    /// Returns the title ID (e.g., "CUSA03173" or "PPSA01234").
+    ///
+    /// The field is 9 bytes, which holds both the PS4 and PS5 formats (four
+    /// letters and five digits). Shorter IDs are cut at the first NUL; a
+    /// longer ID cannot be stored in a content ID.
     #[must_use]
     pub fn title_id(&self) -> &str {
         let bytes = &self.title_id;
@@ -146,6 +169,23 @@ impl ContentId {
         std::str::from_utf8(&bytes[..len]).unwrap_or("")
     }
 
+    /// Returns the namespace of the title ID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::{ContentId, TitleIdKind};
+    ///
+    /// let id = ContentId::from_bytes(b"EP9000-PPSA01234_00-ASTROSPLAYROOM00");
+    ///
+    /// assert_eq!(id.title_id(), "PPSA01234");
+    /// assert_eq!(id.title_id_kind(), TitleIdKind::Ppsa);
+    /// ```
+    #[must_use]
+    pub fn title_id_kind(&self) -> TitleIdKind {
+        TitleIdKind::from_title_id(self.title_id())
+    }
+
     /// Returns the content version (e.g., "00").
     #[must_use]
     pub fn version(&self) -> &str {
@@ -160,6 +200,77 @@ impl ContentId {
         std::str::from_utf8(&bytes[..len]).unwrap_or("")
     }
 
+    /// Returns a filesystem-safe directory name such as
+    /// `UP0102_CUSA03173_00_PSYCHONAUTS1PS40`.
+    ///
+    /// The name joins the service ID with the publisher code, the title ID,
+    /// the content version, and the label with `_`, so the same title from
+    /// different publishers or regions gets different names. Each part is cut
+    /// at the first NUL, and any byte other than an ASCII letter, digit, `-`,
+    /// or `_` is replaced by `_`, so the result is valid on all common
+    /// filesystems. Empty parts are omitted; if all parts are empty,
+    /// `"unknown"` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::ContentId;
+    ///
+    /// let us = ContentId::from_bytes(b"UP0102-CUSA03173_00-PSYCHONAUTS1PS40");
+    /// let eu = ContentId::from_bytes(b"EP0102-CUSA03173_00-PSYCHONAUTS1PS40");
+    ///
+    /// assert_eq!(us.safe_dir_name(), "UP0102_CUSA03173_00_PSYCHONAUTS1PS40");
+    /// assert_ne!(us.safe_dir_name(), eu.safe_dir_name());
+    /// ```
+    #[must_use]
+    pub fn safe_dir_name(&self) -> String {
+        let service = [&self.service_id[..], &self.publisher_code].concat();
+        let mut name = String::new();
+
+        for part in [&service[..], &self.title_id, &self.version, &self.label] {
+            let len = part.iter().position(|&b| b == 0).unwrap_or(part.len());
+
+            if len == 0 {
+                continue;
+            }
+
+            if !name.is_empty() {
+                name.push('_');
+            }
+
+            name.extend(part[..len].iter().map(|&b| {
+                if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+                    b as char
+                } else {
+                    '_'
+                }
+            }));
+        }
+
+        if name.is_empty() {
+            name.push_str("unknown");
+        }
+
+        name
+    }
+
+    /// Returns a wrapper that compares and hashes only the significant bytes.
+    pub fn normalized(self) -> NormalizedContentId {
+        NormalizedContentId(self)
+    }
+
+    /// Returns each field up to its first NUL.
+    fn significant_parts(&self) -> [&[u8]; 5] {
+        [
+            &self.service_id[..],
+            &self.publisher_code,
+            &self.title_id,
+            &self.version,
+            &self.label,
+        ]
+        .map(|part| &part[..part.iter().position(|&b| b == 0).unwrap_or(part.len())])
+    }
+
     /// Returns the full content ID as a string slice.
     #[must_use]
     pub fn as_str(&self) -> &str {
@@ -187,6 +298,80 @@ impl fmt::Debug for ContentId {
     }
 }
 
+/// A [`ContentId`] that compares and hashes only its significant bytes.
+///
+/// Each field is cut at its first NUL before comparing, so padding and any
+/// garbage after it are ignored. Separators are not compared.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::header::ContentId;
+///
+/// let mut a = *b"UP0102-CUSA03173_00-SHORT\0\0\0\0\0\0\0\0\0\0\0";
+/// let b = ContentId::from_bytes(&a);
+/// a[35] = 0xFF; // garbage after the terminator
+/// let c = ContentId::from_bytes(&a);
+///
+/// assert_ne!(b, c);
+/// assert_eq!(b.normalized(), c.normalized());
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[must_use]
+pub struct NormalizedContentId(ContentId);
+
+impl NormalizedContentId {
+    /// Returns the wrapped content ID.
+    #[must_use]
+    pub fn content_id(&self) -> &ContentId {
+        &self.0
+    }
+
+    /// Unwraps the content ID.
+    #[must_use]
+    pub fn into_inner(self) -> ContentId {
+        self.0
+    }
+}
+
+impl From<ContentId> for NormalizedContentId {
+    fn from(value: ContentId) -> Self {
+        Self(value)
+    }
+}
+
+impl PartialEq for NormalizedContentId {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.significant_parts() == other.0.significant_parts()
+    }
+}
+
+impl Eq for NormalizedContentId {}
+
+impl PartialOrd for NormalizedContentId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NormalizedContentId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.significant_parts().cmp(&other.0.significant_parts())
+    }
+}
+
+impl std::hash::Hash for NormalizedContentId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.significant_parts().hash(state);
+    }
+}
+
+impl fmt::Display for NormalizedContentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(
     Clone,
     Copy,
@@ -223,6 +408,14 @@ bitflags::bitflags! {
     }
 }
 
+/// Lists the known flags by name, followed by any bits without a defined flag.
+///
+/// ```
+/// use orbis_pkg::header::ContentFlags;
+///
+/// let flags = ContentFlags::from_bits_retain(0x0010_0001);
+/// assert_eq!(flags.to_string(), "First Patch + unknown bits 0x00000001");
+/// ```
 impl fmt::Display for ContentFlags {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_empty() {
@@ -280,16 +473,32 @@ impl fmt::Display for ContentFlags {
             }
         }
 
+        // Surface bits no flag is defined for.
+        let unknown = self.bits() & !Self::all().bits();
+
+        if unknown != 0 {
+            if !first {
+                write!(f, " + ")?;
+            }
+            write!(f, "unknown bits 0x{:08X}", unknown)?;
+        }
+
         Ok(())
     }
 }
 
+/// The SHA-256 digests stored in the PKG header at 0x100.
 #[derive(Debug, FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
 #[repr(C)]
 pub struct DigestTable {
+    /// Digest of the main entries region (the entry table and the main
+    /// entries' data).
     pub digest_entries1: [u8; 0x20],
+    /// Digest of the main entries' rows of the entry table.
     pub digest_entries2: [u8; 0x20],
+    /// Digest of the digest table entry.
     pub digest_table_digest: [u8; 0x20],
+    /// Digest of the PKG body (all entry data).
     pub digest_body_digest: [u8; 0x20],
 }
 
@@ -321,6 +530,194 @@ pub const fn drm_type_name(drm_type: u32) -> &'static str {
     }
 }
 
+/// What a package installs, derived from its content type and flags by
+/// [`PkgHeader::package_kind()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PackageKind {
+    /// A full, standalone game.
+    BaseGame,
+    /// An update for a game.
+    ///
+    /// A cumulative patch contains every change since the base game; a delta
+    /// patch only the changes since the previous patch, which must also be
+    /// installed.
+    Patch { cumulative: bool },
+    /// Additional content for a game.
+    Dlc,
+    /// A license unlocking additional content, without data of its own.
+    License,
+    /// A non-game application.
+    App,
+    /// The content type is not recognized.
+    Unknown,
+}
+
+impl PackageKind {
+    /// Returns a short display name, such as "Cumulative Patch".
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::BaseGame => "Base Game",
+            Self::Patch { cumulative: true } => "Cumulative Patch",
+            Self::Patch { cumulative: false } => "Delta Patch",
+            Self::Dlc => "DLC",
+            Self::License => "License",
+            Self::App => "App",
+            Self::Unknown => "Unknown",
+        }
+    }
+
+    /// Returns `true` if the package can only be used with its base game
+    /// installed: patches, DLC and licenses.
+    #[must_use]
+    pub const fn requires_base_game(self) -> bool {
+        matches!(self, Self::Patch { .. } | Self::Dlc | Self::License)
+    }
+}
+
+impl fmt::Display for PackageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The console a package targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Platform {
+    /// PlayStation 4.
+    Ps4,
+    /// PlayStation 5.
+    Ps5,
+    /// The platform could not be determined.
+    Unknown,
+}
+
+impl Platform {
+    /// Detects the platform from a title ID such as `CUSA03173` or `PPSA01234`.
+    ///
+    /// Only the four-letter prefix is inspected. PS4 titles use `CUSA` for
+    /// digital releases and `PCxS`/`PLxS`/`PLJM` for disc releases; PS5 titles
+    /// use `PPSA` for digital releases and `ECxS`/`ELxS`/`ELJM` for disc releases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::Platform;
+    ///
+    /// assert_eq!(Platform::from_title_id("CUSA03173"), Platform::Ps4);
+    /// assert_eq!(Platform::from_title_id("PPSA01234"), Platform::Ps5);
+    /// assert_eq!(Platform::from_title_id(""), Platform::Unknown);
+    /// ```
+    #[must_use]
+    pub fn from_title_id(title_id: &str) -> Self {
+        match title_id.get(..4) {
+            Some("CUSA" | "PCAS" | "PCJS" | "PCKS" | "PLAS" | "PLJS" | "PLJM" | "PLKS") => {
+                Self::Ps4
+            }
+            Some("PPSA" | "ECAS" | "ECJS" | "ECKS" | "ELAS" | "ELJS" | "ELJM" | "ELKS") => {
+                Self::Ps5
+            }
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns a short display name ("PS4", "PS5", or "Unknown").
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Ps4 => "PS4",
+            Self::Ps5 => "PS5",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The namespace of a title ID, given by its four-letter prefix.
+///
+/// Unlike [`Platform::from_title_id()`], which also recognizes disc release
+/// prefixes, this only distinguishes the digital namespaces used by the
+/// PlayStation Store from everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TitleIdKind {
+    /// A PS4 title such as `CUSA03173`.
+    Cusa,
+    /// A PS5 title such as `PPSA01234`.
+    Ppsa,
+    /// Any other prefix, e.g. a disc release or a homebrew ID, or an empty or
+    /// malformed title ID.
+    Custom,
+}
+
+impl TitleIdKind {
+    /// Classifies a title ID such as `CUSA03173` or `PPSA01234`.
+    ///
+    /// The prefix must be followed by exactly five ASCII digits; otherwise
+    /// the ID is [`Custom`](Self::Custom).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::TitleIdKind;
+    ///
+    /// assert_eq!(TitleIdKind::from_title_id("CUSA03173"), TitleIdKind::Cusa);
+    /// assert_eq!(TitleIdKind::from_title_id("PPSA01234"), TitleIdKind::Ppsa);
+    /// assert_eq!(TitleIdKind::from_title_id("PLJM16456"), TitleIdKind::Custom);
+    /// assert_eq!(TitleIdKind::from_title_id("CUSA0317"), TitleIdKind::Custom);
+    /// ```
+    #[must_use]
+    pub fn from_title_id(title_id: &str) -> Self {
+        let Some((prefix, number)) = title_id.split_at_checked(4) else {
+            return Self::Custom;
+        };
+
+        if number.len() != 5 || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Self::Custom;
+        }
+
+        match prefix {
+            "CUSA" => Self::Cusa,
+            "PPSA" => Self::Ppsa,
+            _ => Self::Custom,
+        }
+    }
+
+    /// Returns the platform of the namespace, or [`Platform::Unknown`] for
+    /// [`Custom`](Self::Custom).
+    #[must_use]
+    pub const fn platform(self) -> Platform {
+        match self {
+            Self::Cusa => Platform::Ps4,
+            Self::Ppsa => Platform::Ps5,
+            Self::Custom => Platform::Unknown,
+        }
+    }
+
+    /// Returns a short display name ("CUSA", "PPSA", or "Custom").
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cusa => "CUSA",
+            Self::Ppsa => "PPSA",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+impl fmt::Display for TitleIdKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// Parsed PKG header information.
 #[derive(Debug)]
 #[must_use]
@@ -331,12 +728,27 @@ pub struct PkgHeader {
 impl PkgHeader {
     /// Parses a PKG header from raw bytes.
     ///
+    /// Only the first [`HEADER_SIZE`] bytes are read, so a buffer holding just
+    /// the header is enough for inspecting metadata.
+    ///
     /// # Errors
     ///
     /// Returns an error if the data is too small or has an invalid magic number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::{HEADER_SIZE, PkgHeader};
+    ///
+    /// let mut data = vec![0u8; HEADER_SIZE];
+    /// data[..4].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+    ///
+    /// let header = PkgHeader::read(&data).unwrap();
+    /// assert_eq!(header.entry_count(), 0);
+    /// ```
     pub fn read(pkg: &[u8]) -> Result<Self, ReadError> {
         // Check size first so we can read without checking bound.
-        snafu::ensure!(pkg.len() >= 0x1000, TooSmallSnafu);
+        snafu::ensure!(pkg.len() >= HEADER_SIZE, TooSmallSnafu);
 
         let (raw_header, _) =
             PkgHeaderRaw::try_read_from_prefix(pkg).map_err(|_| InvalidSourceBytesSnafu.build())?;
@@ -359,6 +771,20 @@ impl PkgHeader {
         self.raw_header.pkg_table_offset.get() as _
     }
 
+    /// Returns the number of main entries, which come first in the entry
+    /// table.
+    #[must_use]
+    pub const fn sc_entry_count(&self) -> usize {
+        self.raw_header.pkg_sc_entry_count.get() as _
+    }
+
+    /// Returns the size of the main entries region, which starts at the
+    /// entry table and holds the table and the main entries' data.
+    #[must_use]
+    pub const fn entry_data_size(&self) -> usize {
+        self.raw_header.pkg_entry_data_size.get() as _
+    }
+
     /// Returns the offset to the PFS image.
     #[must_use]
     pub const fn pfs_offset(&self) -> usize {
@@ -407,10 +833,98 @@ impl PkgHeader {
         content_type_name(self.content_type())
     }
 
+    /// Returns the platform this package targets.
+    ///
+    /// The title ID prefix is authoritative. If it is not recognized, a
+    /// package is reported as [`Platform::Ps4`] only when both its DRM type
+    /// and content type are known PS4 values; otherwise the result is
+    /// [`Platform::Unknown`].
+    #[must_use]
+    pub fn platform(&self) -> Platform {
+        match Platform::from_title_id(self.content_id().title_id()) {
+            Platform::Unknown
+                if matches!(self.drm_type(), 0x1 | 0xD | 0xF)
+                    && matches!(self.content_type(), 0x1..=0x6 | 0x1A | 0x1B) =>
+            {
+                Platform::Ps4
+            }
+            p => p,
+        }
+    }
+
     /// Returns the content flags.
+    ///
+    /// Bits without a defined flag are kept so they show up when displayed.
     #[must_use]
     pub const fn content_flags(&self) -> ContentFlags {
-        ContentFlags::from_bits_truncate(self.raw_header.pkg_content_flags.get())
+        ContentFlags::from_bits_retain(self.raw_header.pkg_content_flags.get())
+    }
+
+    /// Returns what the package installs.
+    ///
+    /// Patches are usually published with the game data content type and
+    /// told apart by their patch flags, so both are inspected. A game data
+    /// patch is reported as a delta patch only if it has
+    /// [`ContentFlags::DELTA_PATCH_X`]. Content type `0x04` is a delta patch,
+    /// as [`content_type_name()`] names it, unless it has
+    /// [`ContentFlags::CUMULATIVE_PATCH_X`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::header::{HEADER_SIZE, PackageKind, PkgHeader};
+    ///
+    /// let mut data = vec![0u8; HEADER_SIZE];
+    /// data[..4].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+    /// data[0x74..0x78].copy_from_slice(&0x1Au32.to_be_bytes());
+    ///
+    /// let header = PkgHeader::read(&data).unwrap();
+    /// assert_eq!(header.package_kind(), PackageKind::BaseGame);
+    /// assert!(!header.requires_base_game());
+    ///
+    /// // Game data with the delta patch flags.
+    /// data[0x78..0x7C].copy_from_slice(&0x4100_0000u32.to_be_bytes());
+    ///
+    /// let header = PkgHeader::read(&data).unwrap();
+    /// assert_eq!(header.package_kind(), PackageKind::Patch { cumulative: false });
+    /// assert!(header.requires_base_game());
+    ///
+    /// // The delta patch content type without patch flags.
+    /// data[0x74..0x78].copy_from_slice(&0x04u32.to_be_bytes());
+    /// data[0x78..0x7C].fill(0);
+    ///
+    /// let header = PkgHeader::read(&data).unwrap();
+    /// assert_eq!(header.package_kind(), PackageKind::Patch { cumulative: false });
+    /// ```
+    #[must_use]
+    pub const fn package_kind(&self) -> PackageKind {
+        let flags = self.content_flags();
+        let patch = ContentFlags::FIRST_PATCH
+            .union(ContentFlags::SUBSEQUENT_PATCH)
+            .union(ContentFlags::DELTA_PATCH_X)
+            .union(ContentFlags::CUMULATIVE_PATCH_X);
+        let cumulative = !flags.contains(ContentFlags::DELTA_PATCH_X);
+
+        match self.content_type() {
+            0x01 | 0x1A if flags.intersects(patch) => PackageKind::Patch { cumulative },
+            0x01 | 0x1A if flags.contains(ContentFlags::NON_GAME) => PackageKind::App,
+            0x01 | 0x1A | 0x06 => PackageKind::BaseGame,
+            0x04 => PackageKind::Patch {
+                cumulative: flags.contains(ContentFlags::CUMULATIVE_PATCH_X),
+            },
+            0x05 => PackageKind::Patch { cumulative: true },
+            0x02 | 0x1B => PackageKind::Dlc,
+            0x03 => PackageKind::License,
+            _ => PackageKind::Unknown,
+        }
+    }
+
+    /// Returns `true` if the package needs its base game installed.
+    ///
+    /// See [`PackageKind::requires_base_game()`].
+    #[must_use]
+    pub const fn requires_base_game(&self) -> bool {
+        self.package_kind().requires_base_game()
     }
 
     /// Returns the total PKG file size.
@@ -425,6 +939,30 @@ impl PkgHeader {
         self.raw_header.pkg_file_count.get()
     }
 
+    /// Returns the offset of the PKG body, which holds all entry data.
+    #[must_use]
+    pub const fn body_offset(&self) -> u64 {
+        self.raw_header.pkg_body_offset.get()
+    }
+
+    /// Returns the size of the PKG body.
+    #[must_use]
+    pub const fn body_size(&self) -> u64 {
+        self.raw_header.pkg_body_size.get()
+    }
+
+    /// Returns the digest table.
+    #[must_use]
+    pub const fn digest_table(&self) -> &DigestTable {
+        &self.raw_header.digest_table
+    }
+
+    /// Returns the SHA-256 of the header bytes before [`PKG_DIGEST_OFFSET`].
+    #[must_use]
+    pub const fn pkg_digest(&self) -> &[u8; 0x20] {
+        &self.raw_header.pkg_digest
+    }
+
     /// Returns the raw header.
     #[must_use]
     pub const fn raw_header(&self) -> &PkgHeaderRaw {
@@ -0,0 +1,365 @@
+//! Parsing of the `selfinfo.dat` and `imageinfo.dat` entries.
+//!
+//! These entries describe the SELF executables and the executable image of a
+//! package. Both are built from the SELF extended info block (authentication
+//! ID, program type, versions and digest), which is decoded into
+//! [`AuthInfo`]. `imageinfo.dat` adds a flags word after that block, of which
+//! only the compressed bit is decoded.
+//!
+//! The remaining fields are undocumented and may differ between format
+//! versions, so every parsed structure keeps its blob as a [`RawInfo`] for
+//! reading them at raw offsets.
+
+use snafu::Snafu;
+use zerocopy::{
+    FromBytes, Immutable, KnownLayout, Unaligned,
+    byteorder::little_endian::{U32, U64},
+};
+
+use std::fmt;
+
+/// A decrypted info blob with bounds-checked raw field access.
+///
+/// Returned by [`SelfInfo::raw()`] and [`ImageInfo::raw()`] for fields that
+/// are not decoded. Multi-byte accessors read little-endian values, matching
+/// the byte order of SELF metadata on the console.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::info::RawInfo;
+///
+/// let info = RawInfo::new(vec![0x01, 0x00, 0x00, 0x00]);
+/// assert_eq!(info.u32_le(0), Some(1));
+/// assert_eq!(info.u32_le(1), None);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct RawInfo {
+    data: Vec<u8>,
+}
+
+impl RawInfo {
+    /// Wraps the decrypted contents of an info entry.
+    #[must_use]
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Returns the whole blob.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the size of the blob in bytes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the blob is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `len` bytes at `offset`, or `None` if out of bounds.
+    #[must_use]
+    pub fn bytes(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    /// Reads a little-endian `u32` at `offset`, or `None` if out of bounds.
+    #[must_use]
+    pub fn u32_le(&self, offset: usize) -> Option<u32> {
+        self.bytes(offset, 4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    /// Reads a little-endian `u64` at `offset`, or `None` if out of bounds.
+    #[must_use]
+    pub fn u64_le(&self, offset: usize) -> Option<u64> {
+        self.bytes(offset, 8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+}
+
+impl fmt::Debug for RawInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawInfo")
+            .field("len", &self.data.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<Vec<u8>> for RawInfo {
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(data)
+    }
+}
+
+/// Errors of [`ImageInfo::parse()`].
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum ParseError {
+    #[snafu(display("imageinfo.dat is too small"))]
+    TooSmall,
+}
+
+/// SELF extended info block (64 bytes, little-endian).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct AuthInfoRaw {
+    /// 0x00: Program authority ID
+    paid: U64,
+    /// 0x08: Program type
+    program_type: U64,
+    /// 0x10: Application version
+    app_version: U64,
+    /// 0x18: Firmware version
+    fw_version: U64,
+    /// 0x20: SHA-256 digest of the ELF
+    digest: [u8; 0x20],
+}
+
+/// `imageinfo.dat` header (72 bytes, little-endian).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct ImageInfoRaw {
+    /// 0x00: Extended info of the image
+    auth: AuthInfoRaw,
+    /// 0x40: Image flags
+    flags: U32,
+    /// 0x44: Unknown
+    _unknown_44: [u8; 4],
+}
+
+/// Bit of [`ImageInfo::flags()`] set when the image is compressed.
+const IMAGE_FLAG_COMPRESSED: u32 = 0x1;
+
+/// Type of a SELF program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProgramType {
+    Fake,
+    NpdrmExec,
+    NpdrmDynlib,
+    SystemExec,
+    SystemDynlib,
+    HostKernel,
+    SecureModule,
+    SecureKernel,
+    /// Unrecognized program type.
+    Unknown(u64),
+}
+
+impl ProgramType {
+    /// Converts a raw program type into a [`ProgramType`].
+    #[must_use]
+    pub const fn from_u64(raw: u64) -> Self {
+        match raw {
+            0x1 => Self::Fake,
+            0x4 => Self::NpdrmExec,
+            0x5 => Self::NpdrmDynlib,
+            0x8 => Self::SystemExec,
+            0x9 => Self::SystemDynlib,
+            0xc => Self::HostKernel,
+            0xe => Self::SecureModule,
+            0xf => Self::SecureKernel,
+            other => Self::Unknown(other),
+        }
+    }
+
+    /// Returns the raw program type.
+    #[must_use]
+    pub const fn as_u64(self) -> u64 {
+        match self {
+            Self::Fake => 0x1,
+            Self::NpdrmExec => 0x4,
+            Self::NpdrmDynlib => 0x5,
+            Self::SystemExec => 0x8,
+            Self::SystemDynlib => 0x9,
+            Self::HostKernel => 0xc,
+            Self::SecureModule => 0xe,
+            Self::SecureKernel => 0xf,
+            Self::Unknown(raw) => raw,
+        }
+    }
+}
+
+/// Authentication info of a SELF, decoded from its extended info block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthInfo {
+    paid: u64,
+    program_type: ProgramType,
+    app_version: u64,
+    fw_version: u64,
+    digest: [u8; 0x20],
+}
+
+impl AuthInfo {
+    /// Size of the extended info block in bytes.
+    pub const RAW_SIZE: usize = size_of::<AuthInfoRaw>();
+
+    fn from_raw(raw: &AuthInfoRaw) -> Self {
+        Self {
+            paid: raw.paid.get(),
+            program_type: ProgramType::from_u64(raw.program_type.get()),
+            app_version: raw.app_version.get(),
+            fw_version: raw.fw_version.get(),
+            digest: raw.digest,
+        }
+    }
+
+    /// Returns the program authority ID.
+    #[must_use]
+    pub const fn paid(&self) -> u64 {
+        self.paid
+    }
+
+    /// Returns the program type.
+    #[must_use]
+    pub const fn program_type(&self) -> ProgramType {
+        self.program_type
+    }
+
+    /// Returns the application version.
+    #[must_use]
+    pub const fn app_version(&self) -> u64 {
+        self.app_version
+    }
+
+    /// Returns the minimum firmware version.
+    #[must_use]
+    pub const fn fw_version(&self) -> u64 {
+        self.fw_version
+    }
+
+    /// Returns the SHA-256 digest of the ELF.
+    #[must_use]
+    pub const fn digest(&self) -> &[u8; 0x20] {
+        &self.digest
+    }
+}
+
+/// The decoded contents of `selfinfo.dat`.
+///
+/// The entry holds one [`AuthInfo`] block per SELF in the package. A trailing
+/// partial block is not decoded but stays available through [`Self::raw()`].
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::info::{ProgramType, SelfInfo};
+///
+/// let mut data = vec![0u8; 0x40];
+/// data[0x00] = 0x01; // paid
+/// data[0x08] = 0x04; // program type
+/// data.extend_from_slice(&[0xff; 4]);
+///
+/// let info = SelfInfo::parse(&data);
+/// let selfs = info.selfs();
+///
+/// assert_eq!(selfs.len(), 1);
+/// assert_eq!(selfs[0].paid(), 1);
+/// assert_eq!(selfs[0].program_type(), ProgramType::NpdrmExec);
+/// assert_eq!(info.raw().u32_le(0x40), Some(0xffffffff));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfInfo {
+    selfs: Vec<AuthInfo>,
+    raw: RawInfo,
+}
+
+impl SelfInfo {
+    /// Parses the decrypted contents of a `selfinfo.dat` entry.
+    #[must_use]
+    pub fn parse(data: &[u8]) -> Self {
+        let selfs = data
+            .chunks_exact(AuthInfo::RAW_SIZE)
+            .map(|b| AuthInfo::from_raw(AuthInfoRaw::ref_from_bytes(b).unwrap()))
+            .collect();
+
+        Self {
+            selfs,
+            raw: RawInfo::new(data.to_vec()),
+        }
+    }
+
+    /// Returns the authentication info of each SELF.
+    #[must_use]
+    pub fn selfs(&self) -> &[AuthInfo] {
+        &self.selfs
+    }
+
+    /// Returns the whole blob for reading undecoded fields.
+    #[must_use]
+    pub fn raw(&self) -> &RawInfo {
+        &self.raw
+    }
+}
+
+/// The decoded contents of `imageinfo.dat`.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::info::{ImageInfo, ProgramType};
+///
+/// let mut data = vec![0u8; 0x48];
+/// data[0x08] = 0x05; // program type
+/// data[0x40] = 0x01; // compressed
+///
+/// let info = ImageInfo::parse(&data).unwrap();
+///
+/// assert_eq!(info.auth().program_type(), ProgramType::NpdrmDynlib);
+/// assert!(info.is_compressed());
+/// assert!(ImageInfo::parse(&data[..0x40]).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    auth: AuthInfo,
+    flags: u32,
+    raw: RawInfo,
+}
+
+impl ImageInfo {
+    /// Parses the decrypted contents of an `imageinfo.dat` entry.
+    ///
+    /// Bytes after the header are kept and can be read through [`Self::raw()`].
+    pub fn parse(data: &[u8]) -> Result<Self, ParseError> {
+        let (header, _) = ImageInfoRaw::ref_from_prefix(data).map_err(|_| TooSmallSnafu.build())?;
+
+        Ok(Self {
+            auth: AuthInfo::from_raw(&header.auth),
+            flags: header.flags.get(),
+            raw: RawInfo::new(data.to_vec()),
+        })
+    }
+
+    /// Returns the authentication info of the image.
+    #[must_use]
+    pub fn auth(&self) -> &AuthInfo {
+        &self.auth
+    }
+
+    /// Returns the raw image flags.
+    ///
+    /// Only bit 0 (compressed) is interpreted by this crate.
+    #[must_use]
+    pub const fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Returns `true` if the image is compressed (bit 0 of the flags).
+    #[must_use]
+    pub const fn is_compressed(&self) -> bool {
+        self.flags & IMAGE_FLAG_COMPRESSED != 0
+    }
+
+    /// Returns the whole blob for reading undecoded fields.
+    #[must_use]
+    pub fn raw(&self) -> &RawInfo {
+        &self.raw
+    }
+}
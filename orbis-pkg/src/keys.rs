@@ -1,4 +1,67 @@
-use rsa::{BigUint, RsaPrivateKey};
+use rsa::BigUint;
+
+pub use rsa::RsaPrivateKey;
+
+/// Number of entry key slots in a PKG.
+pub const ENTRY_KEY_COUNT: usize = 7;
+
+/// A source of the RSA keys that decrypt the PKG entry key slots.
+///
+/// The entry keys entry holds seven encrypted key slots; an entry encrypted
+/// with slot `n` can only be decrypted if the provider returns a key for `n`.
+/// Use [`Pkg::with_keys()`](crate::Pkg::with_keys) to supply keys beyond the
+/// built-in one.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::keys::{KeyProvider, RsaPrivateKey, pkg_key3};
+///
+/// struct MyKeys {
+///     keys: [Option<RsaPrivateKey>; 7],
+/// }
+///
+/// impl KeyProvider for MyKeys {
+///     fn rsa_key(&self, index: usize) -> Option<&RsaPrivateKey> {
+///         self.keys.get(index)?.as_ref()
+///     }
+/// }
+///
+/// let mut keys = MyKeys { keys: Default::default() };
+/// keys.keys[3] = Some(pkg_key3());
+/// assert!(keys.rsa_key(3).is_some());
+/// assert!(keys.rsa_key(0).is_none());
+/// ```
+pub trait KeyProvider {
+    /// Returns the key for slot `index`, or `None` if it is not available.
+    fn rsa_key(&self, index: usize) -> Option<&RsaPrivateKey>;
+}
+
+/// The [`KeyProvider`] used by [`Pkg::new()`](crate::Pkg::new), carrying only
+/// the publicly known key for slot 3.
+#[derive(Debug, Clone)]
+pub struct DefaultKeys {
+    key3: RsaPrivateKey,
+}
+
+impl DefaultKeys {
+    /// Creates the default provider.
+    pub fn new() -> Self {
+        Self { key3: pkg_key3() }
+    }
+}
+
+impl Default for DefaultKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyProvider for DefaultKeys {
+    fn rsa_key(&self, index: usize) -> Option<&RsaPrivateKey> {
+        (index == 3).then_some(&self.key3)
+    }
+}
 
 pub fn pkg_key3() -> RsaPrivateKey {
     let n = BigUint::from_bytes_be(&[
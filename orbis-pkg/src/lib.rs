@@ -41,19 +41,30 @@
 //! - [PS4 Developer Wiki - PKG files](https://www.psdevwiki.com/ps4/PKG_files)
 
 use self::entry::{EntryId, PkgEntry};
-use self::header::PkgHeader;
-use self::keys::{fake_pfs_key, pkg_key3};
+use self::header::{PkgHeader, Platform};
+use self::info::{ImageInfo, SelfInfo};
+use self::keys::{DefaultKeys, ENTRY_KEY_COUNT, KeyProvider, fake_pfs_key};
+use self::playgo::PlayGoChunks;
+use self::sfo::Sfo;
+use self::source::{PkgSource, ReadSeekSource};
 use aes::cipher::generic_array::GenericArray;
 use aes::cipher::{BlockDecryptMut, KeyIvInit};
 use sha2::Digest;
-use snafu::{ResultExt, Snafu};
-use std::io::Read;
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
 
 use open_error::*;
 
 pub mod entry;
 pub mod header;
+pub mod info;
 pub mod keys;
+pub mod playgo;
+pub mod sfo;
+pub mod source;
 
 /// A parsed PS4 PKG file.
 ///
@@ -62,14 +73,27 @@ pub mod keys;
 ///
 /// Reference: <https://www.psdevwiki.com/ps4/PKG_files>
 #[must_use]
-pub struct Pkg<R: AsRef<[u8]>> {
+pub struct Pkg<R: PkgSource> {
     raw: R,
     header: PkgHeader,
-    entry_key3: Vec<u8>,
+    /// Decrypted entry keys by slot; `None` where no key was available.
+    entry_keys: [Option<Vec<u8>>; ENTRY_KEY_COUNT],
     ekpfs: Vec<u8>,
+    entry_names: OnceLock<Vec<u8>>,
+    /// Entry table index, built by the first [`Pkg::find_entry_raw()`] call.
+    entry_index: OnceLock<EntryIndex>,
 }
 
-impl<R: AsRef<[u8]>> std::fmt::Debug for Pkg<R> {
+/// Maps entry IDs to their position in the entry table.
+struct EntryIndex {
+    /// Table index of the first entry with each ID.
+    ids: BTreeMap<u32, usize>,
+    /// Number of entries indexed; less than the entry count if the table has
+    /// an unreadable entry at this position.
+    len: usize,
+}
+
+impl<R: PkgSource> std::fmt::Debug for Pkg<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Pkg")
             .field("header", &self.header)
@@ -78,7 +102,7 @@ impl<R: AsRef<[u8]>> std::fmt::Debug for Pkg<R> {
     }
 }
 
-impl<R: AsRef<[u8]>> Pkg<R> {
+impl<R: PkgSource> Pkg<R> {
     /// Creates a new [`Pkg`] from raw bytes.
     ///
     /// Parses the header, entry keys, and EKPFS from the provided data.
@@ -95,17 +119,109 @@ impl<R: AsRef<[u8]>> Pkg<R> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// Offsets from a malformed header or entry are rejected instead of
+    /// overflowing.
     pub fn new(raw: R) -> Result<Self, OpenError> {
-        let header = PkgHeader::read(raw.as_ref()).context(ReadHeaderFailedSnafu)?;
+        Self::new_with_options(raw, Strictness::Normal)
+    }
+
+    /// Creates a new [`Pkg`] from raw bytes with the given [`Strictness`].
+    ///
+    /// [`new()`](Self::new) is the same as passing [`Strictness::Normal`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::{Pkg, Strictness};
+    ///
+    /// // A bare header with no entries.
+    /// let mut data = vec![0u8; 0x1000];
+    /// data[..4].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+    ///
+    /// assert!(Pkg::new(&data[..]).is_err());
+    ///
+    /// let pkg = Pkg::new_with_options(&data[..], Strictness::Lenient).unwrap();
+    /// assert_eq!(pkg.entry_count(), 0);
+    /// ```
+    pub fn new_with_options(raw: R, strictness: Strictness) -> Result<Self, OpenError> {
+        Self::open(raw, strictness, &DefaultKeys::new())
+    }
+
+    /// Creates a new [`Pkg`] from raw bytes, decrypting the entry key slots
+    /// with the keys from `provider`.
+    ///
+    /// Every slot the provider has a key for is decrypted, so entries
+    /// encrypted with those slots can be read. A slot whose key fails to
+    /// decrypt it is left empty; the open only fails if no slot could be
+    /// decrypted. [`new_with_options()`](Self::new_with_options) is the same
+    /// as passing [`DefaultKeys`], which only knows slot 3.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::keys::{KeyProvider, RsaPrivateKey};
+    /// use orbis_pkg::{Pkg, Strictness};
+    ///
+    /// struct DumpedKeys(Vec<Option<RsaPrivateKey>>);
+    ///
+    /// impl KeyProvider for DumpedKeys {
+    ///     fn rsa_key(&self, index: usize) -> Option<&RsaPrivateKey> {
+    ///         self.0.get(index)?.as_ref()
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let keys = DumpedKeys(Vec::new());
+    /// let bytes = std::fs::read("game.pkg")?;
+    /// let pkg = Pkg::with_keys(bytes, &keys, Strictness::Normal)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_keys<P: KeyProvider + ?Sized>(
+        raw: R,
+        provider: &P,
+        strictness: Strictness,
+    ) -> Result<Self, OpenError> {
+        Self::open(raw, strictness, provider)
+    }
+
+    fn open<P: KeyProvider + ?Sized>(
+        raw: R,
+        strictness: Strictness,
+        provider: &P,
+    ) -> Result<Self, OpenError> {
+        let mut header = vec![0; raw.size().min(header::HEADER_SIZE as u64) as usize];
+
+        raw.read_exact_at(0, &mut header)
+            .context(ReadSourceFailedSnafu)?;
+
+        let header = PkgHeader::read(&header).context(ReadHeaderFailedSnafu)?;
 
         let mut pkg = Self {
             raw,
             header,
-            entry_key3: Vec::new(),
+            entry_keys: Default::default(),
             ekpfs: Vec::new(),
+            entry_names: OnceLock::new(),
+            entry_index: OnceLock::new(),
         };
-        pkg.load_entry_key3()?;
-        pkg.load_ekpfs()?;
+
+        if strictness == Strictness::Strict {
+            pkg.check_bounds()?;
+        }
+
+        // The EKPFS only needs slot 3, which may have loaded even if another
+        // slot failed, so try both and keep the first error.
+        let keys = pkg.load_entry_keys(provider);
+        let keys = keys.and(pkg.load_ekpfs());
+
+        // Lenient mode keeps whatever keys were loaded; entries needing a missing
+        // key are reported as undecryptable later.
+        if strictness != Strictness::Lenient {
+            keys?;
+        }
+
         Ok(pkg)
     }
 
@@ -114,12 +230,45 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         &self.header
     }
 
+    /// Returns `true` if the package's title ID is `title_id`, ignoring ASCII
+    /// case.
+    ///
+    /// This is meant for filtering a batch of packages by title.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let pkg = orbis_pkg::Pkg::new(std::fs::read("game.pkg")?)?;
+    ///
+    /// if pkg.has_title_id("CUSA03173") {
+    ///     println!("{}", pkg.header().content_id());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn has_title_id(&self, title_id: &str) -> bool {
+        self.header
+            .content_id()
+            .title_id()
+            .eq_ignore_ascii_case(title_id)
+    }
+
     /// Returns the number of entries in the PKG.
     #[must_use]
     pub fn entry_count(&self) -> usize {
         self.header.entry_count()
     }
 
+    /// Returns the platform this package targets.
+    ///
+    /// See [`PkgHeader::platform()`] for how it is detected.
+    #[must_use]
+    pub fn platform(&self) -> Platform {
+        self.header.platform()
+    }
+
     /// Returns an iterator over all entries in the PKG.
     ///
     /// Each item contains the entry index and the entry metadata.
@@ -142,13 +291,24 @@ impl<R: AsRef<[u8]>> Pkg<R> {
     /// ```
     pub fn entries(&self) -> PkgEntries<'_> {
         PkgEntries {
-            raw: self.raw.as_ref(),
+            source: &self.raw,
             table_offset: self.header.table_offset(),
             current: 0,
             total: self.header.entry_count(),
         }
     }
 
+    /// Returns all entries sorted by their data offset.
+    ///
+    /// Processing entries in physical order keeps reads sequential, which helps
+    /// when the backing storage is slow to seek. Each item keeps its original
+    /// table index.
+    pub fn entries_by_offset(&self) -> Result<Vec<(usize, PkgEntry)>, EntryReadError> {
+        let mut entries = self.entries().collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|(_, entry)| entry.data_offset());
+        Ok(entries)
+    }
+
     /// Gets the decrypted data for an entry.
     ///
     /// Returns the decrypted data with any padding removed.
@@ -156,7 +316,10 @@ impl<R: AsRef<[u8]>> Pkg<R> {
     /// # Errors
     ///
     /// Returns [`EntryDataError::NoDecryptionKey`] if the entry is encrypted
-    /// and no decryption key is available for its key index.
+    /// and no decryption key is available for its key index,
+    /// [`EntryDataError::InvalidDataOffset`] if its data range overflows or
+    /// starts past the end of the PKG, and [`EntryDataError::Truncated`] if
+    /// the PKG ends inside it. Malformed entries never cause a panic.
     ///
     /// # Example
     ///
@@ -177,8 +340,95 @@ impl<R: AsRef<[u8]>> Pkg<R> {
     /// # }
     /// ```
     pub fn entry_data(&self, entry: &PkgEntry) -> Result<Vec<u8>, EntryDataError> {
+        let (offset, available, size) = self.entry_region(entry)?;
+
+        snafu::ensure!(
+            available == size,
+            entry_data_error::TruncatedSnafu { size, available }
+        );
+
+        let mut data = vec![0; size];
+
+        self.raw
+            .read_exact_at(offset as u64, &mut data)
+            .context(entry_data_error::ReadSourceFailedSnafu)?;
+
+        // Decrypt if needed.
+        if entry.is_encrypted() {
+            self.decrypt_entry_data(entry, &mut data);
+            // Truncate to actual size (remove padding).
+            data.truncate(entry.data_size());
+        }
+
+        Ok(data)
+    }
+
+    /// Returns a reader over the decrypted data of an entry.
+    ///
+    /// Unlike [`entry_data()`](Self::entry_data), the data is decrypted lazily
+    /// as it is read, so a large entry can be streamed with bounded memory. The
+    /// reader yields exactly [`PkgEntry::data_size()`] bytes.
+    ///
+    /// If the PKG ends inside the cipher padding of an encrypted entry, the
+    /// reader is still created: every whole block is readable and the final,
+    /// partial block fails with [`std::io::ErrorKind::UnexpectedEof`].
+    /// [`entry_data()`](Self::entry_data) returns
+    /// [`EntryDataError::Truncated`] in that case.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::Pkg;
+    /// use orbis_pkg::entry::EntryId;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let bytes = std::fs::read("game.pkg")?;
+    /// let pkg = Pkg::new(bytes)?;
+    ///
+    /// let (entry, _) = pkg.find_entry(EntryId::Pic1Png)?;
+    /// let mut reader = pkg.entry_reader(&entry)?;
+    /// std::io::copy(&mut reader, &mut std::fs::File::create("pic1.png")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry_reader(&self, entry: &PkgEntry) -> Result<EntryReader<'_>, EntryDataError> {
+        let (offset, mut available, size) = self.entry_region(entry)?;
+
+        snafu::ensure!(
+            available >= entry.data_size(),
+            entry_data_error::TruncatedSnafu { size, available }
+        );
+
+        // If the PKG ends inside the padding of the last block, stream the whole
+        // blocks and fail once the partial one is reached.
+        if entry.is_encrypted() {
+            available &= !15;
+        }
+
+        let decryptor = entry.is_encrypted().then(|| {
+            let (key, iv) = self.derive_entry_key(entry);
+            cbc::Decryptor::<aes::Aes128>::new(&key.into(), &iv.into())
+        });
+
+        Ok(EntryReader {
+            source: &self.raw,
+            offset: offset as u64,
+            end: (offset + available) as u64,
+            decryptor,
+            remaining: entry.data_size(),
+            block: [0; 16],
+            block_pos: 16,
+        })
+    }
+
+    /// Returns the offset of an entry's raw data, the number of its bytes that
+    /// are within the PKG, and the full size of its region including padding.
+    ///
+    /// Fewer bytes are available than the region holds if the PKG ends inside
+    /// it.
+    fn entry_region(&self, entry: &PkgEntry) -> Result<(usize, usize, usize), EntryDataError> {
         // Check if we have a decryption key for encrypted entries.
-        if entry.is_encrypted() && (entry.key_index() != 3 || self.entry_key3.is_empty()) {
+        if !self.has_key(entry) {
             return Err(EntryDataError::NoDecryptionKey {
                 key_index: entry.key_index(),
             });
@@ -187,48 +437,332 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         // Get entry data offset and size.
         let offset = entry.data_offset();
         let padded_size = if entry.is_encrypted() {
-            (entry.data_size() + 15) & !15 // Include padding for decryption.
+            // Include padding for decryption.
+            entry
+                .data_size()
+                .checked_add(15)
+                .ok_or(EntryDataError::InvalidDataOffset)?
+                & !15
         } else {
             entry.data_size()
         };
 
-        let raw_data = self
+        checked_range(offset, padded_size).ok_or(EntryDataError::InvalidDataOffset)?;
+
+        let remaining = self
             .raw
-            .as_ref()
-            .get(offset..(offset + padded_size))
+            .size()
+            .checked_sub(offset as u64)
             .ok_or(EntryDataError::InvalidDataOffset)?;
+        let available = remaining.min(padded_size as u64) as usize;
 
-        // Decrypt if needed.
-        if entry.is_encrypted() {
-            if raw_data.len() % 16 != 0 {
-                return Err(EntryDataError::MisalignedData {
-                    size: raw_data.len(),
+        Ok((offset, available, padded_size))
+    }
+
+    /// Returns the name of `entry` from the entry names table.
+    ///
+    /// The table ([`EntryId::EntryNames`]) is read and decrypted on first use
+    /// and cached. Returns `None` if the package has no readable table, the
+    /// entry's name offset is out of range, or the name is empty or not valid
+    /// UTF-8.
+    #[must_use]
+    pub fn entry_name(&self, entry: &PkgEntry) -> Option<String> {
+        let names = self.entry_names.get_or_init(|| {
+            self.find_entry(EntryId::EntryNames)
+                .ok()
+                .and_then(|(names, _)| self.entry_data(&names).ok())
+                .unwrap_or_default()
+        });
+
+        let offset = usize::try_from(entry.filename_offset()).ok()?;
+        let name = names.get(offset..)?;
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+
+        match std::str::from_utf8(name) {
+            Ok(v) if !v.is_empty() => Some(v.to_owned()),
+            _ => None,
+        }
+    }
+
+    /// Returns the output path of `entry` relative to `base`.
+    ///
+    /// Entries with a known ID use [`PkgEntry::to_path()`]. Other entries fall
+    /// back to their name from [`entry_name()`](Self::entry_name), provided it
+    /// is a plain relative path (no root, `.` or `..` components). Returns
+    /// `None` if neither is available.
+    #[must_use]
+    pub fn entry_path<B: AsRef<Path>>(&self, entry: &PkgEntry, base: B) -> Option<PathBuf> {
+        let base = base.as_ref();
+
+        if let Some(path) = entry.to_path(base) {
+            return Some(path);
+        }
+
+        let name = self.entry_name(entry)?;
+        let name = Path::new(&name);
+
+        if name.components().all(|c| matches!(c, Component::Normal(_))) {
+            Some(base.join(name))
+        } else {
+            None
+        }
+    }
+
+    /// Returns why `entry` would be skipped during extraction, or `None` if it
+    /// would be extracted.
+    ///
+    /// This is the check used by [`collect_sce_sys()`](Self::collect_sce_sys)
+    /// and by extraction tools, so a preview built from it matches what is
+    /// actually written.
+    #[must_use]
+    pub fn skip_reason(&self, entry: &PkgEntry) -> Option<SkipReason> {
+        if self.entry_path(entry, "").is_none() {
+            Some(SkipReason::UnknownPath)
+        } else if !self.has_key(entry) {
+            Some(SkipReason::NoDecryptionKey {
+                key_index: entry.key_index(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Lists the entries that extraction would skip, with the reason.
+    ///
+    /// Nothing is decrypted, so this is cheap enough to call before
+    /// extracting, e.g. to warn that some entries have no decryption key.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::{Pkg, SkipReason};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let bytes = std::fs::read("game.pkg")?;
+    /// let pkg = Pkg::new(bytes)?;
+    ///
+    /// let no_key = pkg
+    ///     .extraction_skips()?
+    ///     .iter()
+    ///     .filter(|(_, r)| matches!(r, SkipReason::NoDecryptionKey { .. }))
+    ///     .count();
+    /// println!("{no_key} entries will be skipped (no key)");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extraction_skips(&self) -> Result<Vec<(PkgEntry, SkipReason)>, EntryReadError> {
+        let mut skips = Vec::new();
+
+        for result in self.entries() {
+            let (_, entry) = result?;
+
+            if let Some(reason) = self.skip_reason(&entry) {
+                skips.push((entry, reason));
+            }
+        }
+
+        Ok(skips)
+    }
+
+    /// Decrypts every entry that maps to a file under `sce_sys`.
+    ///
+    /// This is the in-memory counterpart of extracting the entries to disk.
+    /// Paths are relative to the `sce_sys` directory. Entries without a known
+    /// path or without an available decryption key are not returned as files
+    /// but listed in [`SceSys::skipped`] with the reason.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::Pkg;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let bytes = std::fs::read("game.pkg")?;
+    /// let pkg = Pkg::new(bytes)?;
+    ///
+    /// let sce_sys = pkg.collect_sce_sys()?;
+    /// for (path, data) in &sce_sys.files {
+    ///     println!("{}: {} bytes", path.display(), data.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn collect_sce_sys(&self) -> Result<SceSys, CollectSceSysError> {
+        let mut files = Vec::new();
+        let mut skipped = Vec::new();
+
+        for result in self.entries() {
+            let (num, entry) = result.context(collect_sce_sys_error::ReadEntryFailedSnafu)?;
+
+            let path = self.entry_path(&entry, "");
+
+            if let Some(reason) = self.skip_reason(&entry) {
+                skipped.push(SkippedEntry {
+                    num,
+                    id: entry.id(),
+                    path,
+                    reason,
                 });
+                continue;
             }
 
-            let mut decrypted = self.decrypt_entry_data(entry, raw_data);
-            // Truncate to actual size (remove padding).
-            decrypted.truncate(entry.data_size());
-            Ok(decrypted)
-        } else {
-            Ok(raw_data.to_vec())
+            let data = self
+                .entry_data(&entry)
+                .context(collect_sce_sys_error::GetEntryDataFailedSnafu { num })?;
+
+            files.extend(path.map(|p| (p, data)));
         }
+
+        Ok(SceSys { files, skipped })
     }
 
-    /// Returns the embedded PFS image and its encryption key.
+    /// Reads and decrypts the main icon (`icon0.png`).
     ///
-    /// Returns `None` if the PFS offset/size is invalid.
+    /// Falls back to `icon0.dds` when the package has no PNG icon; the returned
+    /// [`Icon::format`] tells which one it is. The data is the file as stored,
+    /// not decoded pixels. Returns `None` if the package has neither icon.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::{IconFormat, Pkg};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let bytes = std::fs::read("game.pkg")?;
+    /// let pkg = Pkg::new(bytes)?;
+    ///
+    /// if let Some(icon) = pkg.icon_png_bytes()? {
+    ///     if icon.format == IconFormat::Png {
+    ///         std::fs::write("icon0.png", &icon.data)?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn icon_png_bytes(&self) -> Result<Option<Icon>, IconError> {
+        for (id, format) in [
+            (EntryId::Icon0Png, IconFormat::Png),
+            (EntryId::Icon0Dds, IconFormat::Dds),
+        ] {
+            let entry = match self.find_entry(id) {
+                Ok((v, _)) => v,
+                Err(FindEntryError::NotFound) => continue,
+                Err(e) => return Err(e).context(icon_error::FindEntryFailedSnafu),
+            };
+
+            let data = self
+                .entry_data(&entry)
+                .context(icon_error::GetEntryDataFailedSnafu)?;
+
+            return Ok(Some(Icon { format, data }));
+        }
+
+        Ok(None)
+    }
+
+    /// Reads, decrypts and parses the `selfinfo.dat` entry.
+    pub fn self_info(&self) -> Result<SelfInfo, InfoEntryError> {
+        self.info_entry(EntryId::SelfinfoDat)
+            .map(|data| SelfInfo::parse(&data))
+    }
+
+    /// Reads, decrypts and parses the `imageinfo.dat` entry.
+    pub fn image_info(&self) -> Result<ImageInfo, ImageInfoError> {
+        let data = self
+            .info_entry(EntryId::ImageinfoDat)
+            .context(image_info_error::ReadEntryFailedSnafu)?;
+
+        ImageInfo::parse(&data).context(image_info_error::ParseFailedSnafu)
+    }
+
+    /// Reads, decrypts and parses the `param.sfo` entry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let data = std::fs::read("game.pkg")?;
+    /// let pkg = orbis_pkg::Pkg::new(data)?;
+    /// let sfo = pkg.param_sfo()?;
+    ///
+    /// println!("{:?} {:?}", sfo.title(), sfo.app_ver());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn param_sfo(&self) -> Result<Sfo, ParamSfoError> {
+        let data = self
+            .info_entry(EntryId::ParamSfo)
+            .context(param_sfo_error::ReadEntryFailedSnafu)?;
+
+        Sfo::parse(&data).context(param_sfo_error::ParseFailedSnafu)
+    }
+
+    /// Reads, decrypts and parses the `playgo-chunk.dat` entry.
+    pub fn playgo_chunks(&self) -> Result<PlayGoChunks, PlayGoChunksError> {
+        let data = self
+            .info_entry(EntryId::PlaygoChunkDat)
+            .context(play_go_chunks_error::ReadEntryFailedSnafu)?;
+
+        PlayGoChunks::parse(&data).context(play_go_chunks_error::ParseFailedSnafu)
+    }
+
+    fn info_entry(&self, id: EntryId) -> Result<Vec<u8>, InfoEntryError> {
+        let (entry, _) = self
+            .find_entry(id)
+            .context(info_entry_error::FindEntryFailedSnafu)?;
+
+        self.entry_data(&entry)
+            .context(info_entry_error::GetEntryDataFailedSnafu)
+    }
+
+    /// Returns a view of the embedded PFS image that reads through the PKG's
+    /// [`PkgSource`].
+    ///
+    /// Unlike [`get_pfs_image()`](Self::get_pfs_image), this works for any
+    /// source, including a [`ReadSeekSource`]. With the `pfs` feature the view
+    /// implements `orbis_pfs::image::Image`, so it can be opened with
+    /// `orbis_pfs::open_image_with_key()`. Returns `None` if the PFS
+    /// offset/size is invalid.
     #[must_use]
-    pub fn get_pfs_image(&self) -> Option<PfsImage<'_>> {
-        let offset = self.header.pfs_offset();
-        let size = self.header.pfs_size();
-        let data = self.raw.as_ref().get(offset..(offset + size))?;
-        Some(PfsImage {
-            data,
+    pub fn pfs_image_view(&self) -> Option<PfsImageView<'_, R>> {
+        let range = checked_range(self.header.pfs_offset(), self.header.pfs_size())?;
+
+        (range.end as u64 <= self.raw.size()).then(|| PfsImageView {
+            source: &self.raw,
+            offset: range.start as u64,
+            len: range.len() as u64,
             ekpfs: &self.ekpfs,
         })
     }
 
+    /// Writes the raw PFS image to `dest`.
+    ///
+    /// The image is copied as stored in the PKG, so an encrypted PFS stays
+    /// encrypted. See `dump_decrypted_pfs_image()` (with the `pfs` feature)
+    /// for a plaintext copy.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`std::io::ErrorKind::InvalidData`] if the PFS offset/size is
+    /// invalid, or any error from reading the source or writing `dest`.
+    pub fn dump_pfs_image(&self, dest: &Path) -> std::io::Result<()> {
+        let image = self.pfs_image_view().ok_or_else(no_pfs_image)?;
+        let mut out = std::io::BufWriter::new(std::fs::File::create(dest)?);
+        let mut buf = vec![0u8; 1024 * 1024];
+        let mut offset = 0;
+
+        while offset < image.len() {
+            let n = (image.len() - offset).min(buf.len() as u64) as usize;
+
+            self.raw
+                .read_exact_at(image.offset() + offset, &mut buf[..n])?;
+            out.write_all(&buf[..n])?;
+            offset += n as u64;
+        }
+
+        out.flush()
+    }
+
     /// Finds an entry by its ID.
     ///
     /// Returns the entry and its index if found.
@@ -239,26 +773,51 @@ impl<R: AsRef<[u8]>> Pkg<R> {
     /// Finds an entry by its raw numeric ID.
     ///
     /// This is useful when working with unknown/unsupported IDs.
+    ///
+    /// The first call indexes the entry table, so later lookups take
+    /// logarithmic time. If several entries share an ID, the first one is
+    /// returned. An unreadable entry is reported if it comes before any entry
+    /// with the ID.
     pub fn find_entry_raw(&self, id: u32) -> Result<(PkgEntry, usize), FindEntryError> {
-        for num in 0..self.header.entry_count() {
-            let offset = self.header.table_offset() + num * PkgEntry::RAW_SIZE;
-            let raw = self
-                .raw
-                .as_ref()
-                .get(offset..(offset + PkgEntry::RAW_SIZE))
-                .ok_or(FindEntryError::InvalidOffset { num })?;
-
-            let entry =
-                PkgEntry::read(raw).map_err(|source| FindEntryError::ReadFailed { source })?;
-
-            if entry.id() == id {
-                return Ok((entry, num));
+        let index = self.entry_index.get_or_init(|| {
+            let mut ids = BTreeMap::new();
+            let mut len = 0;
+
+            while len < self.header.entry_count() {
+                match self.table_entry(len) {
+                    Ok(entry) => ids.entry(entry.id()).or_insert(len),
+                    Err(_) => break,
+                };
+
+                len += 1;
             }
+
+            EntryIndex { ids, len }
+        });
+
+        if let Some(&num) = index.ids.get(&id) {
+            return Ok((self.table_entry(num)?, num));
+        }
+
+        // Report the entry that stopped indexing, if any.
+        if index.len < self.header.entry_count()
+            && let Err(e) = self.table_entry(index.len)
+        {
+            return Err(e);
         }
 
         Err(FindEntryError::NotFound)
     }
 
+    /// Reads entry `num` from the entry table.
+    fn table_entry(&self, num: usize) -> Result<PkgEntry, FindEntryError> {
+        let raw = read_table_entry(&self.raw, self.header.table_offset(), num)
+            .context(find_entry_error::ReadSourceFailedSnafu { num })?
+            .ok_or(FindEntryError::InvalidOffset { num })?;
+
+        PkgEntry::read(&raw).map_err(|source| FindEntryError::ReadFailed { source })
+    }
+
     fn load_ekpfs(&mut self) -> Result<(), OpenError> {
         // Locate image key entry.
         let (entry, _) = match self.find_entry(EntryId::PfsImageKey) {
@@ -283,32 +842,61 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         Ok(())
     }
 
-    fn decrypt_entry_data(&self, entry: &PkgEntry, mut encrypted: &[u8]) -> Vec<u8> {
-        debug_assert_eq!(encrypted.len() % 16, 0);
+    /// Checks that the file is not truncated and every entry's data is in bounds.
+    fn check_bounds(&self) -> Result<(), OpenError> {
+        let actual = self.raw.size();
+        let expected = self.header.pkg_size();
+
+        snafu::ensure!(actual >= expected, TruncatedSnafu { expected, actual });
+
+        for result in self.entries() {
+            let (num, entry) = result.context(ReadEntryFailedSnafu)?;
+
+            snafu::ensure!(
+                checked_range(entry.data_offset(), entry.data_size())
+                    .is_some_and(|r| r.end as u64 <= actual),
+                InvalidEntryOffsetSnafu { num }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if `entry` is unencrypted or its key is available.
+    fn has_key(&self, entry: &PkgEntry) -> bool {
+        !entry.is_encrypted()
+            || self
+                .entry_keys
+                .get(entry.key_index())
+                .is_some_and(Option::is_some)
+    }
+
+    /// Decrypts `data` in place; its length must be a multiple of the block size.
+    fn decrypt_entry_data(&self, entry: &PkgEntry, data: &mut [u8]) {
+        debug_assert_eq!(data.len() % 16, 0);
 
         // Setup decryptor.
-        let (key, iv) = self.derive_entry_key3(entry);
+        let (key, iv) = self.derive_entry_key(entry);
         let mut decryptor = cbc::Decryptor::<aes::Aes128>::new(&key.into(), &iv.into());
 
         // Decrypt blocks.
-        let mut out = Vec::with_capacity(encrypted.len());
-
-        while !encrypted.is_empty() {
-            let mut block = [0u8; 16];
-            encrypted.read_exact(&mut block).unwrap();
-            decryptor.decrypt_block_mut(GenericArray::from_mut_slice(&mut block));
-            out.extend_from_slice(&block);
+        for block in data.chunks_exact_mut(16) {
+            decryptor.decrypt_block_mut(GenericArray::from_mut_slice(block));
         }
-
-        out
     }
 
-    /// Get key and IV for `entry` using `entry_key3`.
-    fn derive_entry_key3(&self, entry: &PkgEntry) -> ([u8; 16], [u8; 16]) {
+    /// Get key and IV for `entry` using its entry key slot.
+    ///
+    /// The slot must hold a key; check with [`has_key()`](Self::has_key) first.
+    fn derive_entry_key(&self, entry: &PkgEntry) -> ([u8; 16], [u8; 16]) {
+        let entry_key = self.entry_keys[entry.key_index()]
+            .as_deref()
+            .expect("entry key slot should be loaded");
+
         // Calculate secret.
         let mut sha256 = sha2::Sha256::new();
         sha256.update(entry.as_bytes());
-        sha256.update(&self.entry_key3);
+        sha256.update(entry_key);
         let secret = sha256.finalize();
 
         // Extract key and IV.
@@ -316,7 +904,7 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         (key.try_into().unwrap(), iv.try_into().unwrap())
     }
 
-    fn load_entry_key3(&mut self) -> Result<(), OpenError> {
+    fn load_entry_keys<P: KeyProvider + ?Sized>(&mut self, provider: &P) -> Result<(), OpenError> {
         // Locate entry keys.
         let (entry, index) = match self.find_entry(EntryId::EntryKeys) {
             Ok(v) => v,
@@ -329,11 +917,10 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         // Get raw entry data (not decrypted, as this contains the keys themselves).
         let offset = entry.data_offset();
         let size = entry.data_size();
-        let mut data = self
-            .raw
-            .as_ref()
-            .get(offset..(offset + size))
+        let data = source::read_range(&self.raw, offset, size)
+            .context(ReadSourceFailedSnafu)?
             .ok_or(OpenError::InvalidEntryOffset { num: index })?;
+        let mut data = &data[..];
 
         // Read seed.
         let mut seed = [0u8; 32];
@@ -342,26 +929,440 @@ impl<R: AsRef<[u8]>> Pkg<R> {
         };
 
         // Read digests.
-        let mut digests: [[u8; 32]; 7] = [[0u8; 32]; 7];
+        let mut digests = [[0u8; 32]; ENTRY_KEY_COUNT];
         digests
             .iter_mut()
             .try_for_each(|digest| data.read_exact(digest))
             .map_err(|_| OpenError::InvalidEntryOffset { num: index })?;
 
         // Read keys.
-        let mut keys: [[u8; 256]; 7] = [[0u8; 256]; 7];
+        let mut keys = [[0u8; 256]; ENTRY_KEY_COUNT];
         keys.iter_mut()
             .try_for_each(|key| data.read_exact(key))
             .map_err(|_| OpenError::InvalidEntryOffset { num: index })?;
 
-        // Decrypt key 3.
-        let key3 = pkg_key3();
-        self.entry_key3 = key3
-            .decrypt(rsa::Pkcs1v15Encrypt, &keys[3])
-            .context(DecryptEntryKeyFailedSnafu { key_index: 3usize })?;
+        // Decrypt every slot we have a key for. A slot that fails stays empty,
+        // so its entries are reported as undecryptable.
+        let mut error = None;
+
+        for (key_index, key) in keys.iter().enumerate() {
+            if let Some(rsa_key) = provider.rsa_key(key_index) {
+                match rsa_key.decrypt(rsa::Pkcs1v15Encrypt, key) {
+                    Ok(entry_key) => self.entry_keys[key_index] = Some(entry_key),
+                    Err(source) => {
+                        error.get_or_insert(OpenError::DecryptEntryKeyFailed { key_index, source });
+                    }
+                }
+            }
+        }
+
+        // Only fail if no slot could be decrypted.
+        match error {
+            Some(e) if self.entry_keys.iter().all(Option::is_none) => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<S: Read + Seek + Send> Pkg<ReadSeekSource<S>> {
+    /// Creates a new [`Pkg`] from a [`Read`] + [`Seek`] stream.
+    ///
+    /// This is the same as wrapping `reader` in a [`ReadSeekSource`] and
+    /// passing it to [`new()`](Self::new). Data is read on demand, so the
+    /// package never has to fit in memory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use orbis_pkg::Pkg;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::io::BufReader::new(std::fs::File::open("game.pkg")?);
+    /// let pkg = Pkg::from_reader(file)?;
+    /// println!("Content ID: {}", pkg.header().content_id());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_reader(reader: S) -> Result<Self, OpenError> {
+        let source = ReadSeekSource::new(reader).context(ReadSourceFailedSnafu)?;
+
+        Self::new(source)
+    }
+}
+
+/// Methods that borrow from the PKG bytes, available when the whole package
+/// is in memory.
+impl<R: AsRef<[u8]> + Sync> Pkg<R> {
+    /// Returns `len` bytes of the raw PKG file starting at `offset`.
+    ///
+    /// Returns `None` if the range is out of bounds.
+    #[must_use]
+    pub fn read_range(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        self.raw.as_ref().get(checked_range(offset, len)?)
+    }
+
+    /// Returns the data of an unencrypted entry as a slice of the PKG.
+    ///
+    /// Unlike [`entry_data()`](Self::entry_data), this borrows from the raw
+    /// PKG bytes, so a memory-mapped package can be written out without an
+    /// intermediate copy. Returns [`EntryDataError::Encrypted`] for encrypted
+    /// entries; use [`entry_data()`](Self::entry_data) or
+    /// [`entry_reader()`](Self::entry_reader) for those.
+    pub fn entry_slice(&self, entry: &PkgEntry) -> Result<&[u8], EntryDataError> {
+        snafu::ensure!(!entry.is_encrypted(), entry_data_error::EncryptedSnafu);
+
+        self.entry_raw_data(entry)
+    }
+
+    /// Returns the raw (still encrypted, if applicable) data of an entry,
+    /// including padding to the cipher block size.
+    fn entry_raw_data(&self, entry: &PkgEntry) -> Result<&[u8], EntryDataError> {
+        let (offset, available, size) = self.entry_region(entry)?;
+        let raw_data = &self.raw.as_ref()[offset..(offset + available)];
+
+        snafu::ensure!(
+            raw_data.len() == size,
+            entry_data_error::TruncatedSnafu {
+                size,
+                available: raw_data.len(),
+            }
+        );
+
+        if entry.is_encrypted() && raw_data.len() % 16 != 0 {
+            return Err(EntryDataError::MisalignedData {
+                size: raw_data.len(),
+            });
+        }
+
+        Ok(raw_data)
+    }
+
+    /// Returns the embedded PFS image and its encryption key.
+    ///
+    /// Returns `None` if the PFS offset/size is invalid.
+    #[must_use]
+    pub fn get_pfs_image(&self) -> Option<PfsImage<'_>> {
+        let offset = self.header.pfs_offset();
+        let size = self.header.pfs_size();
+        let data = self.raw.as_ref().get(checked_range(offset, size)?)?;
+        Some(PfsImage {
+            data,
+            ekpfs: &self.ekpfs,
+        })
+    }
+
+    /// Checks the PKG body against the body digest in the header.
+    ///
+    /// Returns `Ok(true)` if the SHA-256 of the body region matches
+    /// [`DigestTable::digest_body_digest`](header::DigestTable::digest_body_digest).
+    pub fn verify_body_digest(&self) -> Result<bool, VerifyBodyDigestError> {
+        let body = self
+            .body()
+            .context(verify_body_digest_error::InvalidBodyRangeSnafu)?;
+
+        let digest = sha2::Sha256::digest(body);
+
+        Ok(digest[..] == self.header.digest_table().digest_body_digest)
+    }
+
+    /// Checks the header, entry table, digest table and body against the
+    /// digests stored in the header.
+    ///
+    /// The following digests are recomputed, in this order:
+    ///
+    /// - [`PkgHeaderRaw::pkg_digest`](header::PkgHeaderRaw::pkg_digest): the
+    ///   header up to the digest itself.
+    /// - [`DigestTable::digest_entries2`](header::DigestTable::digest_entries2):
+    ///   the rows of the [main entries](header::PkgHeader::sc_entry_count) in
+    ///   the entry table.
+    /// - [`DigestTable::digest_entries1`](header::DigestTable::digest_entries1):
+    ///   the [main entries region](header::PkgHeader::entry_data_size), which
+    ///   holds the entry table and the main entries' data.
+    /// - [`DigestTable::digest_table_digest`](header::DigestTable::digest_table_digest):
+    ///   the data of the [`EntryId::Digests`] entry, which holds the digest of
+    ///   every entry.
+    /// - [`DigestTable::digest_body_digest`](header::DigestTable::digest_body_digest):
+    ///   the body region.
+    ///
+    /// Returns [`VerifyError::Mismatch`] for the first digest that does not
+    /// match. Hashing the body reads the whole file, so this takes time
+    /// proportional to the PKG size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use orbis_pkg::{DigestKind, Pkg, Strictness, VerifyError};
+    /// use sha2::{Digest, Sha256};
+    ///
+    /// // A single main entry, the digests, with 0x20 bytes of data; the main
+    /// // entries region and the body span the table and the data.
+    /// let mut data = vec![0u8; 0x1040];
+    /// data[0x00..0x04].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+    /// data[0x10..0x14].copy_from_slice(&1u32.to_be_bytes());
+    /// data[0x14..0x16].copy_from_slice(&1u16.to_be_bytes());
+    /// data[0x18..0x1C].copy_from_slice(&0x1000u32.to_be_bytes());
+    /// data[0x1C..0x20].copy_from_slice(&0x40u32.to_be_bytes());
+    /// data[0x20..0x28].copy_from_slice(&0x1000u64.to_be_bytes());
+    /// data[0x28..0x30].copy_from_slice(&0x40u64.to_be_bytes());
+    /// data[0x1000..0x1004].copy_from_slice(&1u32.to_be_bytes());
+    /// data[0x1010..0x1014].copy_from_slice(&0x1020u32.to_be_bytes());
+    /// data[0x1014..0x1018].copy_from_slice(&0x20u32.to_be_bytes());
+    /// data[0x1020..0x1040].fill(0xAB);
+    ///
+    /// // Entry digests, digest table, body, then the header that holds them.
+    /// let entries1 = Sha256::digest(&data[0x1000..0x1040]);
+    /// data[0x100..0x120].copy_from_slice(&entries1);
+    /// let entries2 = Sha256::digest(&data[0x1000..0x1020]);
+    /// data[0x120..0x140].copy_from_slice(&entries2);
+    /// let table = Sha256::digest(&data[0x1020..0x1040]);
+    /// data[0x140..0x160].copy_from_slice(&table);
+    /// let body = Sha256::digest(&data[0x1000..0x1040]);
+    /// data[0x160..0x180].copy_from_slice(&body);
+    /// let header = Sha256::digest(&data[..0xFE0]);
+    /// data[0xFE0..0x1000].copy_from_slice(&header);
+    ///
+    /// let pkg = Pkg::new_with_options(&data[..], Strictness::Lenient).unwrap();
+    /// assert!(pkg.verify_digests().is_ok());
+    ///
+    /// // Corruption is caught by the first digest covering it: the reserved
+    /// // field of the entry's row by the entry table digest...
+    /// let mut table = data.clone();
+    /// table[0x101F] ^= 1;
+    /// let pkg = Pkg::new_with_options(&table[..], Strictness::Lenient).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     pkg.verify_digests(),
+    ///     Err(VerifyError::Mismatch {
+    ///         kind: DigestKind::EntryTable
+    ///     })
+    /// ));
+    ///
+    /// // ...and the entry's data by the main entries digest.
+    /// data[0x103F] ^= 1;
+    /// let pkg = Pkg::new_with_options(&data[..], Strictness::Lenient).unwrap();
+    ///
+    /// assert!(matches!(
+    ///     pkg.verify_digests(),
+    ///     Err(VerifyError::Mismatch {
+    ///         kind: DigestKind::MainEntries
+    ///     })
+    /// ));
+    /// ```
+    pub fn verify_digests(&self) -> Result<(), VerifyError> {
+        let raw = self.raw.as_ref();
+        let stored = self.header.digest_table();
+
+        // Header.
+        let header =
+            raw.get(..header::PKG_DIGEST_OFFSET)
+                .context(verify_error::InvalidRangeSnafu {
+                    kind: DigestKind::Header,
+                })?;
+
+        snafu::ensure!(
+            sha2::Sha256::digest(header)[..] == *self.header.pkg_digest(),
+            verify_error::MismatchSnafu {
+                kind: DigestKind::Header,
+            }
+        );
+
+        // Entry table rows of the main entries, then the whole main entries
+        // region.
+        let table_offset = self.header.table_offset();
+        let rows = self
+            .header
+            .sc_entry_count()
+            .checked_mul(PkgEntry::RAW_SIZE)
+            .and_then(|size| self.read_range(table_offset, size))
+            .context(verify_error::InvalidRangeSnafu {
+                kind: DigestKind::EntryTable,
+            })?;
+
+        snafu::ensure!(
+            sha2::Sha256::digest(rows)[..] == stored.digest_entries2,
+            verify_error::MismatchSnafu {
+                kind: DigestKind::EntryTable,
+            }
+        );
+
+        let main = self
+            .read_range(table_offset, self.header.entry_data_size())
+            .context(verify_error::InvalidRangeSnafu {
+                kind: DigestKind::MainEntries,
+            })?;
+
+        snafu::ensure!(
+            sha2::Sha256::digest(main)[..] == stored.digest_entries1,
+            verify_error::MismatchSnafu {
+                kind: DigestKind::MainEntries,
+            }
+        );
+
+        // Digest table.
+        let (entry, _) = self
+            .find_entry(EntryId::Digests)
+            .context(verify_error::FindDigestsFailedSnafu)?;
+        let digests = self
+            .read_range(entry.data_offset(), entry.data_size())
+            .context(verify_error::InvalidRangeSnafu {
+                kind: DigestKind::DigestTable,
+            })?;
+
+        snafu::ensure!(
+            sha2::Sha256::digest(digests)[..] == stored.digest_table_digest,
+            verify_error::MismatchSnafu {
+                kind: DigestKind::DigestTable,
+            }
+        );
+
+        // Body.
+        let body = self.body().context(verify_error::InvalidRangeSnafu {
+            kind: DigestKind::Body,
+        })?;
+
+        snafu::ensure!(
+            sha2::Sha256::digest(body)[..] == stored.digest_body_digest,
+            verify_error::MismatchSnafu {
+                kind: DigestKind::Body,
+            }
+        );
 
         Ok(())
     }
+
+    /// Returns the body region, or `None` if it is out of bounds.
+    fn body(&self) -> Option<&[u8]> {
+        let offset = usize::try_from(self.header.body_offset()).ok()?;
+        let size = usize::try_from(self.header.body_size()).ok()?;
+
+        self.read_range(offset, size)
+    }
+}
+
+/// A streaming reader over the decrypted data of a PKG entry.
+///
+/// Created by [`Pkg::entry_reader()`]. Encrypted data is decrypted one AES
+/// block at a time, or directly into the caller's buffer for whole blocks.
+pub struct EntryReader<'a> {
+    source: &'a (dyn PkgSource + Sync),
+    /// Offset of the raw data not yet consumed.
+    offset: u64,
+    /// End of the readable raw data; for encrypted entries, of its last whole
+    /// block.
+    end: u64,
+    decryptor: Option<cbc::Decryptor<aes::Aes128>>,
+    /// Plaintext bytes left to return.
+    remaining: usize,
+    /// Last decrypted block, for reads that end mid-block.
+    block: [u8; 16],
+    block_pos: usize,
+}
+
+impl EntryReader<'_> {
+    /// Returns the number of bytes left to read.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl std::fmt::Debug for EntryReader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntryReader")
+            .field("encrypted", &self.decryptor.is_some())
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Read for EntryReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.remaining);
+        let buf = &mut buf[..len];
+
+        let Some(decryptor) = &mut self.decryptor else {
+            self.source.read_exact_at(self.offset, buf)?;
+            self.offset += len as u64;
+            self.remaining -= len;
+            return Ok(len);
+        };
+
+        let mut written = 0;
+        let mut error = None;
+
+        while written < len {
+            let out = &mut buf[written..];
+            let available = self.end - self.offset;
+
+            if self.block_pos < 16 {
+                // Drain the buffered block first.
+                let n = out.len().min(16 - self.block_pos);
+                out[..n].copy_from_slice(&self.block[self.block_pos..(self.block_pos + n)]);
+                self.block_pos += n;
+                written += n;
+            } else if available < 16 {
+                // The PKG ends inside the last block; it cannot be decrypted.
+                break;
+            } else if out.len() >= 16 {
+                // Decrypt whole blocks in place.
+                let n = (out.len() as u64).min(available) as usize & !15;
+
+                if let Err(e) = self.source.read_exact_at(self.offset, &mut out[..n]) {
+                    error = Some(e);
+                    break;
+                }
+
+                self.offset += n as u64;
+
+                for block in out[..n].chunks_exact_mut(16) {
+                    decryptor.decrypt_block_mut(GenericArray::from_mut_slice(block));
+                }
+
+                written += n;
+            } else {
+                // Decrypt into the buffer and hand out part of it.
+                if let Err(e) = self.source.read_exact_at(self.offset, &mut self.block) {
+                    error = Some(e);
+                    break;
+                }
+
+                self.offset += 16;
+                decryptor.decrypt_block_mut(GenericArray::from_mut_slice(&mut self.block));
+                self.block_pos = 0;
+            }
+        }
+
+        if written == 0 && len != 0 {
+            return Err(error.unwrap_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "the last cipher block of the entry is truncated",
+                )
+            }));
+        }
+
+        self.remaining -= written;
+
+        Ok(written)
+    }
+}
+
+/// How tolerant [`Pkg::new_with_options()`] is of anomalies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Strictness {
+    /// Fail on any anomaly: in addition to [`Normal`](Self::Normal) checks, the
+    /// file must be at least as large as the header says and every entry's data
+    /// must be in bounds.
+    Strict,
+    /// Fail if the entry keys or the PFS image key cannot be loaded. Entries are
+    /// only checked when they are read.
+    #[default]
+    Normal,
+    /// Parse as much as possible. Missing or undecryptable keys are not errors;
+    /// encrypted entries then fail with [`EntryDataError::NoDecryptionKey`] and
+    /// [`PfsImage::ekpfs`] is empty.
+    Lenient,
 }
 
 /// The embedded PFS image and its encryption key, returned by [`Pkg::get_pfs_image()`].
@@ -373,10 +1374,189 @@ pub struct PfsImage<'a> {
     pub ekpfs: &'a [u8],
 }
 
+/// The embedded PFS image read through a [`PkgSource`], returned by
+/// [`Pkg::pfs_image_view()`].
+///
+/// With the `pfs` feature this implements `orbis_pfs::image::Image`.
+pub struct PfsImageView<'a, R: ?Sized> {
+    source: &'a R,
+    offset: u64,
+    len: u64,
+    ekpfs: &'a [u8],
+}
+
+impl<R: PkgSource + ?Sized> PfsImageView<'_, R> {
+    /// Reads bytes of the PFS image at `offset` into `buf`.
+    ///
+    /// Returns the number of bytes read. A short read indicates the end of
+    /// the image was reached.
+    pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some(remaining) = self.len.checked_sub(offset) else {
+            return Ok(0);
+        };
+        let n = (buf.len() as u64).min(remaining) as usize;
+
+        self.source.read_at(self.offset + offset, &mut buf[..n])
+    }
+
+    /// Returns the length of the PFS image in bytes.
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if the PFS image is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the offset of the PFS image within the PKG.
+    #[must_use]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the EKPFS key needed to decrypt and open the PFS.
+    #[must_use]
+    pub fn ekpfs(&self) -> &[u8] {
+        self.ekpfs
+    }
+}
+
+impl<R: ?Sized> std::fmt::Debug for PfsImageView<'_, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PfsImageView")
+            .field("offset", &self.offset)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "pfs")]
+impl<R: PkgSource + Sync> Pkg<R> {
+    /// Writes the PFS image to `dest`, decrypted with the package's EKPFS.
+    ///
+    /// The image is streamed through the XTS decryptor one sector at a time
+    /// with `orbis_pfs::decrypt_image()`; the header of the copy still has
+    /// the encrypted mode flag set. An unencrypted image is written as is,
+    /// like [`dump_pfs_image()`](Self::dump_pfs_image).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`std::io::ErrorKind::InvalidData`] if the PFS offset/size is
+    /// invalid, an error wrapping `orbis_pfs::DecryptError` if the image
+    /// cannot be decrypted (for example without an EKPFS in
+    /// [`Strictness::Lenient`] mode), or any error from reading the source or
+    /// writing `dest`.
+    pub fn dump_decrypted_pfs_image(&self, dest: &Path) -> std::io::Result<()> {
+        use orbis_pfs::image::Image;
+
+        let image = self.pfs_image_view().ok_or_else(no_pfs_image)?;
+        let image = match orbis_pfs::decrypt_image(image, &self.ekpfs) {
+            Ok(v) => v,
+            Err(orbis_pfs::DecryptError::NotEncrypted) => return self.dump_pfs_image(dest),
+            Err(e) => return Err(std::io::Error::other(e)),
+        };
+        let mut out = std::io::BufWriter::new(std::fs::File::create(dest)?);
+
+        image.dump_to(&mut out, 1024 * 1024)?;
+        out.flush()
+    }
+}
+
+/// Opening the view as a PFS:
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let pkg = orbis_pkg::Pkg::from_reader(std::fs::File::open("game.pkg")?)?;
+/// let image = pkg.pfs_image_view().ok_or("no PFS image")?;
+/// let ekpfs = image.ekpfs().to_vec();
+/// let pfs = orbis_pfs::open_image_with_key(image, Some(&ekpfs))?;
+///
+/// println!("{} inodes", pfs.inode_count());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "pfs")]
+impl<R: PkgSource + Sync + ?Sized> orbis_pfs::image::Image for PfsImageView<'_, R> {
+    fn read_at(&self, offset: u64, output_buf: &mut [u8]) -> std::io::Result<usize> {
+        PfsImageView::read_at(self, offset, output_buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn describe(&self) -> String {
+        format!("PfsImageView(offset={}, len={})", self.offset, self.len)
+    }
+}
+
+/// The main icon of a package, returned by [`Pkg::icon_png_bytes()`].
+#[derive(Debug, Clone)]
+pub struct Icon {
+    /// The file format of [`Icon::data`].
+    pub format: IconFormat,
+    /// The icon file as stored in the package.
+    pub data: Vec<u8>,
+}
+
+/// The file format of an [`Icon`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IconFormat {
+    /// `icon0.png`.
+    Png,
+    /// `icon0.dds`.
+    Dds,
+}
+
+/// Decrypted `sce_sys` entries, returned by [`Pkg::collect_sce_sys()`].
+#[derive(Debug, Default)]
+pub struct SceSys {
+    /// Decrypted files as `(path, data)` pairs, with paths relative to `sce_sys`.
+    pub files: Vec<(PathBuf, Vec<u8>)>,
+    /// Entries that were not decrypted, with the reason.
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// An entry that was skipped while collecting or extracting entries.
+#[derive(Debug, Clone)]
+pub struct SkippedEntry {
+    /// Index of the entry in the entry table.
+    pub num: usize,
+    /// Raw entry ID.
+    pub id: u32,
+    /// Relative path of the entry, if it has one.
+    pub path: Option<PathBuf>,
+    /// Why the entry was skipped.
+    pub reason: SkipReason,
+}
+
+/// The reason an entry was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The entry ID has no known file path and the entry has no usable name.
+    UnknownPath,
+    /// The entry is encrypted with a key that is not available.
+    NoDecryptionKey { key_index: usize },
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPath => write!(f, "unknown path"),
+            Self::NoDecryptionKey { key_index } => write!(f, "no key for index {}", key_index),
+        }
+    }
+}
+
 /// Iterator over PKG entries.
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct PkgEntries<'a> {
-    raw: &'a [u8],
+    source: &'a (dyn PkgSource + Sync),
     table_offset: usize,
     current: usize,
     total: usize,
@@ -402,14 +1582,14 @@ impl Iterator for PkgEntries<'_> {
         let num = self.current;
         self.current += 1;
 
-        let offset = self.table_offset + num * PkgEntry::RAW_SIZE;
-        let raw = match self.raw.get(offset..(offset + PkgEntry::RAW_SIZE)) {
-            Some(v) => v,
-            None => return Some(Err(EntryReadError::InvalidOffset { num })),
+        let raw = match read_table_entry(self.source, self.table_offset, num) {
+            Ok(Some(v)) => v,
+            Ok(None) => return Some(Err(EntryReadError::InvalidOffset { num })),
+            Err(source) => return Some(Err(EntryReadError::ReadSourceFailed { num, source })),
         };
 
         Some(
-            PkgEntry::read(raw)
+            PkgEntry::read(&raw)
                 .map_err(|source| EntryReadError::ReadFailed { source })
                 .map(|entry| (num, entry)),
         )
@@ -423,10 +1603,53 @@ impl Iterator for PkgEntries<'_> {
 
 impl ExactSizeIterator for PkgEntries<'_> {}
 
+/// Error for a PFS image dump from a PKG whose PFS offset/size is invalid.
+fn no_pfs_image() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "PKG has no valid PFS image",
+    )
+}
+
+/// Returns `offset..offset + len`, or `None` if the end overflows.
+fn checked_range(offset: usize, len: usize) -> Option<std::ops::Range<usize>> {
+    Some(offset..offset.checked_add(len)?)
+}
+
+/// Returns the byte range of entry `num` in the table at `table_offset`.
+fn entry_range(table_offset: usize, num: usize) -> Option<std::ops::Range<usize>> {
+    let offset = num
+        .checked_mul(PkgEntry::RAW_SIZE)?
+        .checked_add(table_offset)?;
+
+    checked_range(offset, PkgEntry::RAW_SIZE)
+}
+
+/// Reads entry `num` of the table at `table_offset`, or returns `None` if it
+/// is out of bounds.
+fn read_table_entry<S: PkgSource + ?Sized>(
+    source: &S,
+    table_offset: usize,
+    num: usize,
+) -> std::io::Result<Option<[u8; PkgEntry::RAW_SIZE]>> {
+    let Some(range) = entry_range(table_offset, num).filter(|r| r.end as u64 <= source.size())
+    else {
+        return Ok(None);
+    };
+    let mut raw = [0; PkgEntry::RAW_SIZE];
+
+    source.read_exact_at(range.start as u64, &mut raw)?;
+
+    Ok(Some(raw))
+}
+
 #[derive(Debug, Snafu)]
 #[snafu(module)]
 #[non_exhaustive]
 pub enum OpenError {
+    #[snafu(display("failed to read from the PKG source"))]
+    ReadSourceFailed { source: std::io::Error },
+
     #[snafu(display("invalid PKG header"))]
     ReadHeaderFailed { source: header::ReadError },
 
@@ -456,6 +1679,12 @@ pub enum OpenError {
 
     #[snafu(display("cannot decrypt EKPFS"))]
     DecryptEkpfsFailed { source: rsa::errors::Error },
+
+    #[snafu(display("PKG is truncated ({actual} of {expected} bytes)"))]
+    Truncated { expected: u64, actual: u64 },
+
+    #[snafu(display("failed to read entry"))]
+    ReadEntryFailed { source: EntryReadError },
 }
 
 #[derive(Debug, Snafu)]
@@ -468,6 +1697,9 @@ pub enum FindEntryError {
     #[snafu(display("entry #{num} has invalid offset"))]
     InvalidOffset { num: usize },
 
+    #[snafu(display("failed to read entry #{num} from the PKG source"))]
+    ReadSourceFailed { num: usize, source: std::io::Error },
+
     #[snafu(display("the specified entry was not found"))]
     NotFound,
 }
@@ -481,6 +1713,125 @@ pub enum EntryReadError {
 
     #[snafu(display("failed to read entry"))]
     ReadFailed { source: entry::EntryError },
+
+    #[snafu(display("failed to read entry #{num} from the PKG source"))]
+    ReadSourceFailed { num: usize, source: std::io::Error },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum CollectSceSysError {
+    #[snafu(display("failed to read entry"))]
+    ReadEntryFailed { source: EntryReadError },
+
+    #[snafu(display("failed to get data for entry #{num}"))]
+    GetEntryDataFailed { num: usize, source: EntryDataError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum IconError {
+    #[snafu(display("failed to find icon entry"))]
+    FindEntryFailed { source: FindEntryError },
+
+    #[snafu(display("failed to get icon data"))]
+    GetEntryDataFailed { source: EntryDataError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum VerifyBodyDigestError {
+    #[snafu(display("PKG body range is out of bounds"))]
+    InvalidBodyRange,
+}
+
+/// A digest checked by [`Pkg::verify_digests()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DigestKind {
+    /// The digest of the header.
+    Header,
+    /// The digest of the main entries' rows of the entry table.
+    EntryTable,
+    /// The digest of the main entries region.
+    MainEntries,
+    /// The digest of the digests entry.
+    DigestTable,
+    /// The digest of the body.
+    Body,
+}
+
+impl std::fmt::Display for DigestKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header => write!(f, "header"),
+            Self::EntryTable => write!(f, "entry table"),
+            Self::MainEntries => write!(f, "main entries"),
+            Self::DigestTable => write!(f, "digest table"),
+            Self::Body => write!(f, "body"),
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum VerifyError {
+    #[snafu(display("{kind} range is out of bounds"))]
+    InvalidRange { kind: DigestKind },
+
+    #[snafu(display("failed to find the digests entry"))]
+    FindDigestsFailed { source: FindEntryError },
+
+    #[snafu(display("{kind} digest does not match"))]
+    Mismatch { kind: DigestKind },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum InfoEntryError {
+    #[snafu(display("failed to find entry"))]
+    FindEntryFailed { source: FindEntryError },
+
+    #[snafu(display("failed to get entry data"))]
+    GetEntryDataFailed { source: EntryDataError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum ImageInfoError {
+    #[snafu(display("failed to read imageinfo.dat"))]
+    ReadEntryFailed { source: InfoEntryError },
+
+    #[snafu(display("failed to parse imageinfo.dat"))]
+    ParseFailed { source: info::ParseError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum ParamSfoError {
+    #[snafu(display("failed to read param.sfo"))]
+    ReadEntryFailed { source: InfoEntryError },
+
+    #[snafu(display("failed to parse param.sfo"))]
+    ParseFailed { source: sfo::SfoError },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum PlayGoChunksError {
+    #[snafu(display("failed to read playgo-chunk.dat"))]
+    ReadEntryFailed { source: InfoEntryError },
+
+    #[snafu(display("failed to parse playgo-chunk.dat"))]
+    ParseFailed { source: playgo::ParseError },
 }
 
 #[derive(Debug, Snafu)]
@@ -490,11 +1841,92 @@ pub enum EntryDataError {
     #[snafu(display("no decryption key available for key index {key_index}"))]
     NoDecryptionKey { key_index: usize },
 
+    /// The entry's data range overflows `usize` or starts past the end of
+    /// the PKG.
     #[snafu(display("entry has invalid data offset"))]
     InvalidDataOffset,
 
+    /// The entry starts within the PKG but the file ends before its data (or,
+    /// for encrypted entries, its cipher padding) does.
+    #[snafu(display("entry data is truncated ({available} of {size} bytes present)"))]
+    Truncated { size: usize, available: usize },
+
     #[snafu(display(
         "encrypted entry data is not block-aligned (size {size} is not a multiple of 16)"
     ))]
     MisalignedData { size: usize },
+
+    #[snafu(display("entry is encrypted and cannot be borrowed"))]
+    Encrypted,
+
+    #[snafu(display("failed to read entry data from the PKG source"))]
+    ReadSourceFailed { source: std::io::Error },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryDataError, OpenError, Pkg, Strictness};
+
+    /// Returns a bare header with the PKG magic.
+    fn header() -> Vec<u8> {
+        let mut data = vec![0u8; 0x1000];
+        data[0x00..0x04].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn overflowing_offsets_are_rejected_on_open() {
+        let mut data = header();
+
+        // Entry table at the maximum offset.
+        data[0x10..0x14].copy_from_slice(&u32::MAX.to_be_bytes());
+        data[0x18..0x1C].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            Pkg::new(&data[..]),
+            Err(OpenError::FindEntryKeyFailed { .. })
+        ));
+
+        // Entry keys with maximum data offset and size.
+        data[0x10..0x14].copy_from_slice(&1u32.to_be_bytes());
+        data[0x18..0x1C].copy_from_slice(&0x100u32.to_be_bytes());
+        data[0x100..0x104].copy_from_slice(&0x10u32.to_be_bytes());
+        data[0x110..0x114].copy_from_slice(&u32::MAX.to_be_bytes());
+        data[0x114..0x118].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            Pkg::new(&data[..]),
+            Err(OpenError::InvalidEntryOffset { num: 0 })
+        ));
+    }
+
+    #[test]
+    fn truncated_and_overflowing_entry_data_is_rejected() {
+        // One unencrypted entry with 0x40 bytes of data after the table.
+        let mut data = header();
+        data.resize(0x1060, 0);
+        data[0x10..0x14].copy_from_slice(&1u32.to_be_bytes());
+        data[0x18..0x1C].copy_from_slice(&0x1000u32.to_be_bytes());
+        data[0x1000..0x1004].copy_from_slice(&0x1000u32.to_be_bytes());
+        data[0x1010..0x1014].copy_from_slice(&0x1020u32.to_be_bytes());
+        data[0x1014..0x1018].copy_from_slice(&0x40u32.to_be_bytes());
+
+        for len in 0x1020..data.len() {
+            let pkg = Pkg::new_with_options(&data[..len], Strictness::Lenient).unwrap();
+            let (entry, _) = pkg.find_entry_raw(0x1000).unwrap();
+
+            assert!(matches!(
+                pkg.entry_data(&entry),
+                Err(EntryDataError::Truncated { .. } | EntryDataError::InvalidDataOffset)
+            ));
+        }
+
+        // Data offset and size at their maximum.
+        data[0x1010..0x1018].fill(0xFF);
+        let pkg = Pkg::new_with_options(&data[..], Strictness::Lenient).unwrap();
+        let (entry, _) = pkg.find_entry_raw(0x1000).unwrap();
+
+        assert!(matches!(
+            pkg.entry_data(&entry),
+            Err(EntryDataError::InvalidDataOffset)
+        ));
+    }
 }
@@ -0,0 +1,314 @@
+//! Parsing of the PlayGo chunk table (`playgo-chunk.dat`).
+//!
+//! PlayGo splits an application into install chunks (base game, languages,
+//! optional content) so the console can start the game before everything is
+//! installed. Each chunk is made of "mchunks", which are byte ranges of the
+//! PFS image. This module decodes the chunk table and exposes the ranges of
+//! each chunk within the inner PFS image (`pfs_image.dat`), which is what is
+//! needed to decide which files belong to a chunk.
+//!
+//! Only the chunk and mchunk tables are decoded. Scenarios and the outer image
+//! mchunks are ignored.
+
+use snafu::{OptionExt, Snafu};
+use std::ops::Range;
+use zerocopy::{
+    FromBytes, Immutable, KnownLayout, Unaligned,
+    byteorder::little_endian::{U16, U32, U64},
+};
+
+use parse_error::*;
+
+const PLAYGO_MAGIC: &[u8; 4] = b"plgo";
+
+/// Mask of the byte offset in an mchunk location; the upper bits hold image
+/// and layer numbers.
+const MCHUNK_OFFSET_MASK: u64 = (1 << 48) - 1;
+
+/// Errors of [`PlayGoChunks::parse()`].
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum ParseError {
+    #[snafu(display("PlayGo chunk table is too small"))]
+    TooSmall,
+
+    #[snafu(display("invalid PlayGo magic"))]
+    InvalidMagic,
+
+    #[snafu(display("{table} table is out of bounds"))]
+    TableOutOfBounds { table: &'static str },
+
+    #[snafu(display("chunk #{id} is out of bounds"))]
+    InvalidChunk { id: u16 },
+
+    #[snafu(display("chunk #{id} refers to unknown mchunk #{mchunk}"))]
+    InvalidMchunk { id: u16, mchunk: u16 },
+}
+
+/// Location of a table within the file.
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct TableRef {
+    offset: U32,
+    size: U32,
+}
+
+/// PlayGo chunk table header (256 bytes, little-endian).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct PlayGoHeader {
+    /// 0x00: Magic bytes "plgo"
+    magic: [u8; 4],
+    /// 0x04: Major and minor version
+    _version: [U16; 2],
+    /// 0x08: Number of images
+    _image_count: U16,
+    /// 0x0A: Number of chunks
+    chunk_count: U16,
+    /// 0x0C: Number of mchunks
+    _mchunk_count: U16,
+    /// 0x0E: Number of scenarios
+    _scenario_count: U16,
+    /// 0x10: File size, default scenario, attributes, SDK version, disc
+    /// count and layer bitmap
+    _unknown_10: [u8; 0x10],
+    /// 0x20: Reserved
+    _reserved: [u8; 0x20],
+    /// 0x40: Content ID
+    _content_id: [u8; 0x80],
+    /// 0xC0: Chunk attributes
+    chunk_attrs: TableRef,
+    /// 0xC8: Mchunk indices of each chunk
+    chunk_mchunks: TableRef,
+    /// 0xD0: Chunk labels
+    chunk_labels: TableRef,
+    /// 0xD8: Mchunk ranges in the outer image
+    _mchunk_attrs: TableRef,
+    /// 0xE0: Scenario attributes, chunks and labels
+    _scenarios: [TableRef; 3],
+    /// 0xF8: Mchunk ranges in the inner image
+    inner_mchunk_attrs: TableRef,
+}
+
+/// Chunk attribute entry (32 bytes).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct ChunkAttr {
+    /// 0x00: Flags, disc layer and required locus
+    _unknown_00: [u8; 0xE],
+    /// 0x0E: Number of mchunks
+    mchunk_count: U16,
+    /// 0x10: Languages the chunk applies to
+    language_mask: U64,
+    /// 0x18: Byte offset of the mchunk indices within the chunk mchunks table
+    mchunks_offset: U32,
+    /// 0x1C: Byte offset of the label within the chunk labels table
+    label_offset: U32,
+}
+
+/// Mchunk attribute entry (16 bytes).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct MchunkAttr {
+    location: U64,
+    size: U64,
+}
+
+/// The decoded contents of `playgo-chunk.dat`.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let data = std::fs::read("game.pkg")?;
+/// let pkg = orbis_pkg::Pkg::new(data)?;
+///
+/// for chunk in pkg.playgo_chunks()?.chunks() {
+///     println!("#{} {:?} {:?}", chunk.id(), chunk.label(), chunk.ranges());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayGoChunks {
+    chunks: Vec<PlayGoChunk>,
+}
+
+impl PlayGoChunks {
+    /// Parses the decrypted contents of a `playgo-chunk.dat` entry.
+    ///
+    /// ```
+    /// use orbis_pkg::playgo::PlayGoChunks;
+    ///
+    /// // Header, one chunk attribute at 0x100, its mchunk list at 0x120, its
+    /// // label at 0x124 and two adjacent inner mchunks at 0x130.
+    /// let mut data = vec![0u8; 0x150];
+    /// let table = |data: &mut Vec<u8>, at: usize, offset: u32, size: u32| {
+    ///     data[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+    ///     data[at + 4..at + 8].copy_from_slice(&size.to_le_bytes());
+    /// };
+    ///
+    /// data[..4].copy_from_slice(b"plgo");
+    /// data[0x0A..0x0C].copy_from_slice(&1u16.to_le_bytes());
+    /// table(&mut data, 0xC0, 0x100, 0x20);
+    /// table(&mut data, 0xC8, 0x120, 4);
+    /// table(&mut data, 0xD0, 0x124, 8);
+    /// table(&mut data, 0xF8, 0x130, 0x20);
+    ///
+    /// data[0x10E] = 2; // mchunk count
+    /// data[0x120..0x124].copy_from_slice(&[0, 0, 1, 0]);
+    /// data[0x124..0x128].copy_from_slice(b"base");
+    /// data[0x130..0x138].copy_from_slice(&0x10000u64.to_le_bytes());
+    /// data[0x138..0x140].copy_from_slice(&0x8000u64.to_le_bytes());
+    /// data[0x140..0x148].copy_from_slice(&0x18000u64.to_le_bytes());
+    /// data[0x148..0x150].copy_from_slice(&0x8000u64.to_le_bytes());
+    ///
+    /// let chunks = PlayGoChunks::parse(&data).unwrap();
+    /// let base = chunks.get(0).unwrap();
+    ///
+    /// assert_eq!(base.label(), "base");
+    /// assert_eq!(base.ranges(), [0x10000..0x20000]);
+    /// assert!(base.overlaps(&(0x1F000..0x21000)));
+    /// assert!(!base.overlaps(&(0x20000..0x21000)));
+    /// ```
+    pub fn parse(data: &[u8]) -> Result<Self, ParseError> {
+        let (header, _) = PlayGoHeader::ref_from_prefix(data)
+            .ok()
+            .context(TooSmallSnafu)?;
+
+        snafu::ensure!(&header.magic == PLAYGO_MAGIC, InvalidMagicSnafu);
+
+        let chunk_count = header.chunk_count.get();
+        let attrs = table(data, &header.chunk_attrs, "chunk attribute")?;
+        let mchunk_ids = table(data, &header.chunk_mchunks, "chunk mchunk")?;
+        let labels = table(data, &header.chunk_labels, "chunk label")?;
+        let mchunks = table(data, &header.inner_mchunk_attrs, "inner mchunk attribute")?;
+        let mchunks = <[MchunkAttr]>::ref_from_prefix_with_elems(
+            mchunks,
+            mchunks.len() / size_of::<MchunkAttr>(),
+        )
+        .expect("table length is a multiple of the element size")
+        .0;
+
+        let mut chunks = Vec::with_capacity(chunk_count.into());
+
+        for id in 0..chunk_count {
+            let attr = attrs
+                .get(usize::from(id) * size_of::<ChunkAttr>()..)
+                .and_then(|b| ChunkAttr::ref_from_prefix(b).ok())
+                .context(InvalidChunkSnafu { id })?
+                .0;
+
+            // Mchunk indices are u16s at a byte offset in the mchunk table.
+            let ids_start = attr.mchunks_offset.get() as usize;
+            let ids_len = usize::from(attr.mchunk_count.get()) * 2;
+            let ids = ids_start
+                .checked_add(ids_len)
+                .and_then(|end| mchunk_ids.get(ids_start..end))
+                .context(InvalidChunkSnafu { id })?;
+
+            let mut ranges: Vec<Range<u64>> = Vec::with_capacity(ids.len() / 2);
+
+            for raw in ids.chunks_exact(2) {
+                let mchunk = u16::from_le_bytes([raw[0], raw[1]]);
+                let attr = mchunks
+                    .get(usize::from(mchunk))
+                    .context(InvalidMchunkSnafu { id, mchunk })?;
+                let start = attr.location.get() & MCHUNK_OFFSET_MASK;
+                let end = start.saturating_add(attr.size.get());
+
+                match ranges.last_mut() {
+                    Some(last) if last.end == start => last.end = end,
+                    _ => ranges.push(start..end),
+                }
+            }
+
+            // Labels are NUL-terminated; a missing label is not fatal.
+            let label = labels
+                .get(attr.label_offset.get() as usize..)
+                .map(|b| {
+                    let len = b.iter().position(|&c| c == 0).unwrap_or(b.len());
+                    String::from_utf8_lossy(&b[..len]).into_owned()
+                })
+                .unwrap_or_default();
+
+            chunks.push(PlayGoChunk {
+                id,
+                language_mask: attr.language_mask.get(),
+                label,
+                ranges,
+            });
+        }
+
+        Ok(Self { chunks })
+    }
+
+    /// Returns all chunks, ordered by ID.
+    #[must_use]
+    pub fn chunks(&self) -> &[PlayGoChunk] {
+        &self.chunks
+    }
+
+    /// Returns the chunk with the given ID.
+    #[must_use]
+    pub fn get(&self, id: u16) -> Option<&PlayGoChunk> {
+        self.chunks.get(usize::from(id))
+    }
+}
+
+/// A PlayGo install chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayGoChunk {
+    id: u16,
+    language_mask: u64,
+    label: String,
+    ranges: Vec<Range<u64>>,
+}
+
+impl PlayGoChunk {
+    /// Returns the chunk ID. Chunk #0 is the base game.
+    #[must_use]
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    /// Returns the bit mask of languages this chunk is meant for.
+    #[must_use]
+    pub fn language_mask(&self) -> u64 {
+        self.language_mask
+    }
+
+    /// Returns the chunk label, or an empty string if it has none.
+    #[must_use]
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the byte ranges of the inner PFS image covered by this chunk.
+    ///
+    /// Adjacent mchunks are merged.
+    #[must_use]
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// Returns `true` if any part of `range` falls inside this chunk.
+    #[must_use]
+    pub fn overlaps(&self, range: &Range<u64>) -> bool {
+        self.ranges
+            .iter()
+            .any(|r| r.start < range.end && range.start < r.end)
+    }
+}
+
+/// Returns the bytes of `table`, or an error naming it if it is out of bounds.
+fn table<'a>(data: &'a [u8], table: &TableRef, name: &'static str) -> Result<&'a [u8], ParseError> {
+    let start = table.offset.get() as usize;
+    let size = table.size.get() as usize;
+
+    start
+        .checked_add(size)
+        .and_then(|end| data.get(start..end))
+        .context(TableOutOfBoundsSnafu { table: name })
+}
@@ -0,0 +1,272 @@
+//! Parsing of system file objects (`param.sfo`).
+//!
+//! A `param.sfo` is a small key/value table describing the application: its
+//! title, title ID, version, category and so on. Keys are ASCII strings and
+//! values are either UTF-8 strings or 32-bit integers.
+//!
+//! Reference: <https://www.psdevwiki.com/ps4/Param.sfo>
+
+use snafu::{OptionExt, Snafu};
+use std::fmt;
+use zerocopy::{
+    FromBytes, Immutable, KnownLayout, Unaligned,
+    byteorder::little_endian::{U16, U32},
+};
+
+use sfo_error::*;
+
+const SFO_MAGIC: &[u8; 4] = b"\0PSF";
+
+/// Value format of UTF-8 data that is not NUL-terminated.
+const FMT_UTF8_SPECIAL: u16 = 0x0004;
+/// Value format of NUL-terminated UTF-8 strings.
+const FMT_UTF8: u16 = 0x0204;
+/// Value format of 32-bit integers.
+const FMT_INT32: u16 = 0x0404;
+
+/// Errors of [`Sfo::parse()`].
+#[derive(Debug, Snafu)]
+#[snafu(module)]
+#[non_exhaustive]
+pub enum SfoError {
+    #[snafu(display("param.sfo is too small"))]
+    TooSmall,
+
+    #[snafu(display("invalid param.sfo magic"))]
+    InvalidMagic,
+
+    #[snafu(display("index entry #{num} is out of bounds"))]
+    InvalidIndexEntry { num: usize },
+
+    #[snafu(display("key of entry #{num} is out of bounds or not valid UTF-8"))]
+    InvalidKey { num: usize },
+
+    #[snafu(display("value of {key} is out of bounds"))]
+    InvalidValue { key: String },
+}
+
+/// SFO header (20 bytes).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct SfoHeader {
+    /// 0x00: Magic bytes "\0PSF"
+    magic: [u8; 4],
+    /// 0x04: Format version
+    _version: U32,
+    /// 0x08: Offset of the key table
+    key_table_start: U32,
+    /// 0x0C: Offset of the data table
+    data_table_start: U32,
+    /// 0x10: Number of entries
+    entry_count: U32,
+}
+
+/// SFO index entry (16 bytes).
+#[derive(FromBytes, KnownLayout, Immutable, Unaligned)]
+#[repr(C)]
+struct IndexEntry {
+    /// 0x00: Offset of the key within the key table
+    key_offset: U16,
+    /// 0x02: Value format
+    format: U16,
+    /// 0x04: Length of the value in bytes
+    len: U32,
+    /// 0x08: Space reserved for the value
+    _max_len: U32,
+    /// 0x0C: Offset of the value within the data table
+    data_offset: U32,
+}
+
+/// A value in a [`Sfo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SfoValue {
+    /// A UTF-8 string, without its terminating NUL.
+    Str(String),
+    /// A 32-bit integer.
+    Int(u32),
+    /// A value of an unknown format, or a string that is not valid UTF-8.
+    Bytes(Vec<u8>),
+}
+
+impl SfoValue {
+    /// Returns the string, or `None` if this is not a string.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer, or `None` if this is not an integer.
+    #[must_use]
+    pub fn as_int(&self) -> Option<u32> {
+        match self {
+            Self::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SfoValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(v) => f.write_str(v),
+            Self::Int(v) => write!(f, "{v:#x}"),
+            Self::Bytes(v) => {
+                for b in v {
+                    write!(f, "{b:02x}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A parsed `param.sfo`.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::sfo::{Sfo, SfoValue};
+///
+/// // Header, two index entries, the key table and the data table.
+/// let mut data = Vec::new();
+/// data.extend_from_slice(b"\0PSF");
+/// data.extend_from_slice(&0x101u32.to_le_bytes());
+/// data.extend_from_slice(&0x34u32.to_le_bytes()); // key table
+/// data.extend_from_slice(&0x4Cu32.to_le_bytes()); // data table
+/// data.extend_from_slice(&2u32.to_le_bytes());
+///
+/// for (key, fmt, len, max, offset) in [(0u16, 0x0204u16, 5u32, 8u32, 0u32), (9, 0x0404, 4, 4, 8)] {
+///     data.extend_from_slice(&key.to_le_bytes());
+///     data.extend_from_slice(&fmt.to_le_bytes());
+///     data.extend_from_slice(&len.to_le_bytes());
+///     data.extend_from_slice(&max.to_le_bytes());
+///     data.extend_from_slice(&offset.to_le_bytes());
+/// }
+///
+/// data.extend_from_slice(b"APP_VER\0\0PARENTAL_LEVEL\0");
+/// data.extend_from_slice(b"01.00\0\0\0");
+/// data.extend_from_slice(&9u32.to_le_bytes());
+///
+/// let sfo = Sfo::parse(&data).unwrap();
+///
+/// assert_eq!(sfo.app_ver(), Some("01.00"));
+/// assert_eq!(sfo.get("PARENTAL_LEVEL"), Some(&SfoValue::Int(9)));
+/// assert_eq!(sfo.title(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sfo {
+    entries: Vec<(String, SfoValue)>,
+}
+
+impl Sfo {
+    /// Parses the contents of a `param.sfo`.
+    pub fn parse(data: &[u8]) -> Result<Self, SfoError> {
+        let (header, _) = SfoHeader::ref_from_prefix(data)
+            .ok()
+            .context(TooSmallSnafu)?;
+
+        snafu::ensure!(&header.magic == SFO_MAGIC, InvalidMagicSnafu);
+
+        let keys = data
+            .get(header.key_table_start.get() as usize..)
+            .context(TooSmallSnafu)?;
+        let values = data
+            .get(header.data_table_start.get() as usize..)
+            .context(TooSmallSnafu)?;
+        let count = header.entry_count.get() as usize;
+        let mut entries = Vec::with_capacity(count.min(256));
+
+        for num in 0..count {
+            let entry = data
+                .get(size_of::<SfoHeader>() + num * size_of::<IndexEntry>()..)
+                .and_then(|b| IndexEntry::ref_from_prefix(b).ok())
+                .context(InvalidIndexEntrySnafu { num })?
+                .0;
+
+            let key = keys
+                .get(usize::from(entry.key_offset.get())..)
+                .and_then(|b| {
+                    let len = b.iter().position(|&c| c == 0)?;
+                    std::str::from_utf8(&b[..len]).ok()
+                })
+                .context(InvalidKeySnafu { num })?;
+
+            let start = entry.data_offset.get() as usize;
+            let value = start
+                .checked_add(entry.len.get() as usize)
+                .and_then(|end| values.get(start..end))
+                .context(InvalidValueSnafu { key })?;
+
+            let value = match entry.format.get() {
+                FMT_INT32 if value.len() == 4 => {
+                    SfoValue::Int(u32::from_le_bytes(value.try_into().unwrap()))
+                }
+                FMT_UTF8 | FMT_UTF8_SPECIAL => {
+                    let len = value.iter().position(|&c| c == 0).unwrap_or(value.len());
+
+                    match std::str::from_utf8(&value[..len]) {
+                        Ok(v) => SfoValue::Str(v.to_owned()),
+                        Err(_) => SfoValue::Bytes(value.to_vec()),
+                    }
+                }
+                _ => SfoValue::Bytes(value.to_vec()),
+            };
+
+            entries.push((key.to_owned(), value));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Returns the value of `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&SfoValue> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns all keys and values in file order.
+    #[must_use]
+    pub fn entries(&self) -> &[(String, SfoValue)] {
+        &self.entries
+    }
+
+    /// Returns the `TITLE` string.
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.get_str("TITLE")
+    }
+
+    /// Returns the `TITLE_ID` string, such as `CUSA00001`.
+    #[must_use]
+    pub fn title_id(&self) -> Option<&str> {
+        self.get_str("TITLE_ID")
+    }
+
+    /// Returns the `APP_VER` string, such as `01.00`.
+    #[must_use]
+    pub fn app_ver(&self) -> Option<&str> {
+        self.get_str("APP_VER")
+    }
+
+    /// Returns the `CONTENT_ID` string.
+    #[must_use]
+    pub fn content_id(&self) -> Option<&str> {
+        self.get_str("CONTENT_ID")
+    }
+
+    /// Returns the `CATEGORY` string, such as `gd` for a game or `gp` for a
+    /// patch.
+    #[must_use]
+    pub fn category(&self) -> Option<&str> {
+        self.get_str("CATEGORY")
+    }
+
+    fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(SfoValue::as_str)
+    }
+}
@@ -0,0 +1,166 @@
+//! Positional read access to the bytes of a PKG.
+//!
+//! [`Pkg`](crate::Pkg) reads the header, entry table and entry data through
+//! [`PkgSource`]. Every `AsRef<[u8]>` (a `Vec<u8>`, a byte slice, a memory
+//! map) is a source, and [`ReadSeekSource`] adapts any [`Read`] + [`Seek`]
+//! stream, so a package does not have to be loaded into one contiguous buffer.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Mutex;
+
+/// Random-access byte source a [`Pkg`](crate::Pkg) is read from.
+///
+/// Sources are `Sync`, so the iterators and readers borrowing one from a
+/// [`Pkg`](crate::Pkg) can be sent to other threads:
+///
+/// ```
+/// use orbis_pkg::{EntryReader, PkgEntries};
+///
+/// fn assert_send<T: Send>() {}
+///
+/// assert_send::<PkgEntries<'_>>();
+/// assert_send::<EntryReader<'_>>();
+/// ```
+pub trait PkgSource: Sync {
+    /// Reads bytes at `offset` into `buf`.
+    ///
+    /// Returns the number of bytes read. A short read indicates the end of
+    /// the source was reached.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Reads exactly `buf.len()` bytes at `offset`.
+    ///
+    /// Returns [`io::ErrorKind::UnexpectedEof`] if the source ends before the
+    /// buffer is filled.
+    fn read_exact_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut total = 0;
+
+        while total < buf.len() {
+            let n = self.read_at(offset + total as u64, &mut buf[total..])?;
+
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "unexpected EOF in PKG source",
+                ));
+            }
+
+            total += n;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the total size of the source in bytes.
+    ///
+    /// Not named `len` so it does not shadow `len()` on byte slices, which
+    /// implement this trait.
+    fn size(&self) -> u64;
+}
+
+impl<T: AsRef<[u8]> + Sync + ?Sized> PkgSource for T {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.as_ref();
+        let Some(src) = usize::try_from(offset).ok().and_then(|o| data.get(o..)) else {
+            return Ok(0);
+        };
+        let n = src.len().min(buf.len());
+
+        buf[..n].copy_from_slice(&src[..n]);
+
+        Ok(n)
+    }
+
+    fn size(&self) -> u64 {
+        self.as_ref().len() as u64
+    }
+}
+
+/// A [`PkgSource`] over a [`Read`] + [`Seek`] stream.
+///
+/// Each read seeks to its offset first, so reads are serialized through a
+/// mutex. The length is taken once, when the source is created.
+///
+/// # Example
+///
+/// ```
+/// use orbis_pkg::source::ReadSeekSource;
+/// use orbis_pkg::{Pkg, Strictness};
+/// use std::io::Cursor;
+///
+/// // A bare header with no entries.
+/// let mut data = vec![0u8; 0x1000];
+/// data[..4].copy_from_slice(&0x7F434E54u32.to_be_bytes());
+///
+/// let source = ReadSeekSource::new(Cursor::new(data)).unwrap();
+/// let pkg = Pkg::new_with_options(source, Strictness::Lenient).unwrap();
+///
+/// assert_eq!(pkg.entry_count(), 0);
+/// ```
+pub struct ReadSeekSource<R> {
+    inner: Mutex<R>,
+    len: u64,
+}
+
+impl<R: Read + Seek> ReadSeekSource<R> {
+    /// Wraps `inner`, seeking to its end to find the length.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+
+        Ok(Self {
+            inner: Mutex::new(inner),
+            len,
+        })
+    }
+
+    /// Returns the wrapped stream.
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<R> std::fmt::Debug for ReadSeekSource<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadSeekSource")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: Read + Seek + Send> PkgSource for ReadSeekSource<R> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        if offset >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let len = buf
+            .len()
+            .min((self.len - offset).try_into().unwrap_or(usize::MAX));
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        inner.seek(SeekFrom::Start(offset))?;
+        inner.read(&mut buf[..len])
+    }
+
+    fn size(&self) -> u64 {
+        self.len
+    }
+}
+
+/// Reads `len` bytes at `offset`, or returns `None` if the range is out of
+/// bounds.
+pub(crate) fn read_range<S: PkgSource + ?Sized>(
+    source: &S,
+    offset: usize,
+    len: usize,
+) -> io::Result<Option<Vec<u8>>> {
+    let end = match offset.checked_add(len) {
+        Some(v) if v as u64 <= source.size() => v,
+        _ => return Ok(None),
+    };
+    let mut buf = vec![0; end - offset];
+
+    source.read_exact_at(offset as u64, &mut buf)?;
+
+    Ok(Some(buf))
+}